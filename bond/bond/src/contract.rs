@@ -205,8 +205,9 @@ impl BondTrait for Bond {
             panic_with_error!(&e, Error::NotAvailable)
         }
 
-        // Check if end time has passed
-        if e.ledger().timestamp() < read_end_time(&e) {
+        // Check if end time has passed. An unset end time never passes
+        let end_time = read_end_time(&e);
+        if end_time == 0 || e.ledger().timestamp() < end_time {
             panic_with_error!(&e, Error::EndTimeNotPassed)
         }
 
@@ -234,6 +235,10 @@ impl BondTrait for Bond {
         if !check_user(&e, &(e.invoker().into())) {
             panic_with_error!(&e, Error::UserNotAllowed)
         }
+        // Check if the initial time has been reached
+        if e.ledger().timestamp() < read_init_time(&e) {
+            panic_with_error!(&e, Error::NotStarted)
+        }
 
         increase_supply(&e, amount);
 
@@ -288,17 +293,25 @@ impl BondTrait for Bond {
 }
 
 fn current_price(e: &Env) -> i128 {
-    let mut end_time = read_end_time(&e);
+    let init_time = read_init_time(&e);
     let now = e.ledger().timestamp();
+    let initial_price = read_price(&e);
 
-    // If the end date has not passed yet
-    if now < end_time {
+    // Before the initial time the price does not change
+    if now <= init_time {
+        return initial_price;
+    }
+
+    let mut end_time = read_end_time(&e);
+
+    // If the end date is not set (open-ended) or has not passed yet
+    if end_time == 0 || now < end_time {
         end_time = now;
     }
 
-    let initial_price = read_price(&e);
-    // Calculates the amount of time intervals that have passed
-    let time = (end_time - read_init_time(&e)) / read_fee_interval(&e);
+    // Calculates the amount of time intervals that have passed.
+    // An end date set before the initial time means no interval has passed
+    let time = end_time.saturating_sub(init_time) / read_fee_interval(&e);
 
     // If no time interval has passed, the price does not change
     if time == 0 {
@@ -16,4 +16,5 @@ pub enum Error {
     UserAlreadyAllowed = 10,
     UserNotAllowed = 11,
     NotPaused = 12,
+    NotStarted = 13,
 }
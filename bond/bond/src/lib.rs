@@ -6,6 +6,8 @@ mod event;
 mod metadata;
 mod storage_types;
 
+#[cfg(test)]
+mod prop_test;
 #[cfg(test)]
 mod test;
 pub use crate::contract::Bond;
@@ -0,0 +1,452 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::contract::{token, Bond, BondClient};
+use crate::storage_types::InterestType;
+use crate::test::{
+    create_token_contract, days_to_seconds, generate_user_account, updates_contract_time,
+};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use soroban_auth::{Identifier, Signature};
+use soroban_sdk::testutils::Accounts;
+use soroban_sdk::{AccountId, BytesN, Env, IntoVal};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::vec::Vec;
+
+use token::Client as TokenClient;
+
+// Bond parameters shared by every generated lifecycle
+const PRICE: i128 = 100;
+const FEE_INTERVAL_DAYS: u64 = 30;
+const INITIAL_AMOUNT: i128 = 10000;
+const USER_FUNDS: i128 = 100000;
+const ADMIN_FUNDS: i128 = 1000000;
+
+// User 0 and 1 are whitelisted, user 2 is never allowed to buy
+const USERS: usize = 3;
+const ALLOWED_USERS: usize = 2;
+
+// The compound formula overflows i128 after a dozen intervals, so keep
+// the valuation term within 11 intervals, including open-ended lifecycles:
+// at most 120 + 31 * 8 days of ledger time since an initial time of 30 days
+const MAX_TERM_DAYS: u64 = 10 * FEE_INTERVAL_DAYS;
+const MAX_ADVANCE_DAYS: u64 = 8;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Advance(u64), // seconds
+    Buy(usize, i128),
+    Pause,
+    Unpause,
+    SetEnd(u64), // seconds after the initial time
+    Withdraw(i128),
+    Fund(i128), // admin transfers payment tokens to the contract
+    EnCshOut,
+    CashOut(usize),
+}
+
+#[derive(Clone, Debug)]
+struct Lifecycle {
+    fee_type: InterestType,
+    fee_rate: i128,
+    start_time: u64,        // ledger time when the contract is started
+    init_time: u64,         // may be before or after the start time
+    end_time: Option<u64>,  // may be before the initial time
+    end_before_start: bool, // call set_end before start
+    ops: Vec<Op>,
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (1..=days_to_seconds(MAX_ADVANCE_DAYS)).prop_map(Op::Advance),
+        4 => (0..USERS, 1..=50_i128).prop_map(|(user, amount)| Op::Buy(user, amount)),
+        1 => Just(Op::Pause),
+        1 => Just(Op::Unpause),
+        1 => (0..=days_to_seconds(MAX_TERM_DAYS)).prop_map(Op::SetEnd),
+        1 => (1..=5000_i128).prop_map(Op::Withdraw),
+        2 => (1..=50000_i128).prop_map(Op::Fund),
+        2 => Just(Op::EnCshOut),
+        2 => (0..USERS).prop_map(Op::CashOut),
+    ]
+}
+
+fn lifecycle_strategy() -> impl Strategy<Value = Lifecycle> {
+    (
+        prop_oneof![Just(InterestType::Simple), Just(InterestType::Compound)],
+        1..=100_i128,
+        days_to_seconds(60)..=days_to_seconds(120),
+        days_to_seconds(30)..=days_to_seconds(180),
+        proptest::option::of(days_to_seconds(30)..=days_to_seconds(180 + MAX_TERM_DAYS)),
+        any::<bool>(),
+        vec(op_strategy(), 1..32),
+    )
+        .prop_map(
+            |(fee_type, fee_rate, start_time, init_time, end_time, end_before_start, ops)| {
+                Lifecycle {
+                    fee_type,
+                    fee_rate,
+                    start_time,
+                    init_time,
+                    end_time,
+                    end_before_start,
+                    ops,
+                }
+            },
+        )
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ModelState {
+    Available,
+    Paused,
+    CashOutEn,
+}
+
+struct UserModel {
+    allowed: bool,
+    payment: i128,
+    allowance: i128,
+    bonds: i128,
+}
+
+// Reference model of the contract, following the states table of the README
+struct Model {
+    state: ModelState,
+    fee_type: InterestType,
+    fee_rate: i128,
+    init_time: u64,
+    end_time: u64,
+    now: u64,
+    sold: i128,
+    supply: i128,
+    contract_balance: i128,
+    admin_balance: i128,
+    users: Vec<UserModel>,
+}
+
+impl Model {
+    fn price(&self) -> i128 {
+        // An unset end time (0) is open-ended
+        let end_time = match self.end_time {
+            0 => self.now,
+            end_time => self.now.min(end_time),
+        };
+        // Before the initial time, or with an end time set before it,
+        // no interval has passed
+        let intervals =
+            end_time.saturating_sub(self.init_time) / days_to_seconds(FEE_INTERVAL_DAYS);
+
+        match self.fee_type {
+            InterestType::Simple => PRICE + PRICE * (intervals as i128) * self.fee_rate / 1000,
+            InterestType::Compound => {
+                // Accumulate numerator and denominator, rounding only once
+                let mut num = PRICE;
+                let mut den = 1_i128;
+                for _ in 0..intervals {
+                    num *= 1000 + self.fee_rate;
+                    den *= 1000;
+                }
+                num / den
+            }
+        }
+    }
+}
+
+struct Harness {
+    e: Env,
+    contract_id: BytesN<32>,
+    contract: BondClient,
+    contract_identifier: Identifier,
+    admin: AccountId,
+    payment_tkn: TokenClient,
+    bond_tkn: TokenClient,
+    users: Vec<(AccountId, Identifier)>,
+    model: Model,
+}
+
+// Returns true if the call was rejected by the contract
+fn rejects<F: FnOnce()>(f: F) -> bool {
+    catch_unwind(AssertUnwindSafe(f)).is_err()
+}
+
+impl Harness {
+    fn new(lifecycle: &Lifecycle) -> Self {
+        let e: Env = Default::default();
+
+        let admin = e.accounts().generate();
+        let admin_id = Identifier::Account(admin.clone());
+        let payment_tkn_admin = e.accounts().generate();
+
+        let (payment_tkn_id, payment_tkn) =
+            create_token_contract(&e, &payment_tkn_admin, "USD Coin", "USDC", 8);
+
+        let contract_id = e.register_contract(None, Bond);
+        let contract = updates_contract_time(&e, contract_id.clone(), lifecycle.start_time);
+        let contract_identifier = Identifier::Contract(contract_id.clone());
+
+        let mut users = Vec::new();
+        let mut user_models = Vec::new();
+        for _ in 0..USERS {
+            users.push(generate_user_account(
+                &e,
+                &payment_tkn,
+                &payment_tkn_admin,
+                &contract_identifier,
+                &USER_FUNDS,
+            ));
+            user_models.push(UserModel {
+                allowed: false,
+                payment: USER_FUNDS,
+                allowance: USER_FUNDS,
+                bonds: 0,
+            });
+        }
+
+        payment_tkn.with_source_account(&payment_tkn_admin).mint(
+            &Signature::Invoker,
+            &0,
+            &admin_id,
+            &ADMIN_FUNDS,
+        );
+
+        contract.initialize(
+            &admin_id,
+            &payment_tkn_id,
+            &"Bond".into_val(&e),
+            &"BND".into_val(&e),
+            &8,
+            &PRICE,
+            &lifecycle.fee_rate,
+            &FEE_INTERVAL_DAYS,
+            &lifecycle.fee_type,
+            &INITIAL_AMOUNT,
+        );
+        let bond_tkn = TokenClient::new(&e, contract.bond_id());
+
+        let init_time = lifecycle.init_time;
+        let mut end_time = 0;
+        if lifecycle.end_before_start {
+            // Before start any end time is accepted, even one before the initial time
+            if let Some(end) = lifecycle.end_time {
+                contract.with_source_account(&admin).set_end(&end);
+                end_time = end;
+            }
+            contract.with_source_account(&admin).start(&init_time);
+        } else {
+            contract.with_source_account(&admin).start(&init_time);
+            if let Some(end) = lifecycle.end_time {
+                let client = contract.with_source_account(&admin);
+                if end < init_time {
+                    assert!(rejects(|| client.set_end(&end)), "set_end must be rejected");
+                } else {
+                    client.set_end(&end);
+                    end_time = end;
+                }
+            }
+        }
+
+        for (user, user_model) in users.iter().zip(user_models.iter_mut()).take(ALLOWED_USERS) {
+            contract.with_source_account(&admin).add_user(&user.1);
+            user_model.allowed = true;
+        }
+
+        let model = Model {
+            state: ModelState::Available,
+            fee_type: lifecycle.fee_type.clone(),
+            fee_rate: lifecycle.fee_rate,
+            init_time,
+            end_time,
+            now: lifecycle.start_time,
+            sold: 0,
+            supply: 0,
+            contract_balance: 0,
+            admin_balance: ADMIN_FUNDS,
+            users: user_models,
+        };
+
+        Harness {
+            e,
+            contract_id,
+            contract,
+            contract_identifier,
+            admin,
+            payment_tkn,
+            bond_tkn,
+            users,
+            model,
+        }
+    }
+
+    fn apply(&mut self, op: &Op) {
+        match *op {
+            Op::Advance(seconds) => {
+                self.model.now += seconds;
+                self.contract =
+                    updates_contract_time(&self.e, self.contract_id.clone(), self.model.now);
+            }
+            Op::Buy(user, amount) => {
+                let total = self.model.price() * amount;
+                let user_model = &self.model.users[user];
+                let valid = self.model.state == ModelState::Available
+                    && user_model.allowed
+                    && self.model.now >= self.model.init_time
+                    && INITIAL_AMOUNT - self.model.sold >= amount
+                    && user_model.payment >= total
+                    && user_model.allowance >= total;
+
+                let client = self.contract.with_source_account(&self.users[user].0);
+                if !valid {
+                    assert!(rejects(|| client.buy(&amount)), "buy must be rejected");
+                    return;
+                }
+                client.buy(&amount);
+
+                let user_model = &mut self.model.users[user];
+                user_model.payment -= total;
+                user_model.allowance -= total;
+                user_model.bonds += amount;
+                self.model.sold += amount;
+                self.model.supply += amount;
+                self.model.contract_balance += total;
+            }
+            Op::Pause => {
+                let client = self.contract.with_source_account(&self.admin);
+                if self.model.state != ModelState::Available {
+                    assert!(rejects(|| client.pause()), "pause must be rejected");
+                    return;
+                }
+                client.pause();
+                self.model.state = ModelState::Paused;
+            }
+            Op::Unpause => {
+                let client = self.contract.with_source_account(&self.admin);
+                if self.model.state != ModelState::Paused {
+                    assert!(rejects(|| client.unpause()), "unpause must be rejected");
+                    return;
+                }
+                client.unpause();
+                self.model.state = ModelState::Available;
+            }
+            Op::SetEnd(offset) => {
+                let end_time = self.model.init_time + offset;
+                let client = self.contract.with_source_account(&self.admin);
+                if self.model.state == ModelState::CashOutEn {
+                    assert!(
+                        rejects(|| client.set_end(&end_time)),
+                        "set_end must be rejected"
+                    );
+                    return;
+                }
+                client.set_end(&end_time);
+                self.model.end_time = end_time;
+            }
+            Op::Withdraw(amount) => {
+                let client = self.contract.with_source_account(&self.admin);
+                if self.model.state == ModelState::CashOutEn || self.model.contract_balance < amount
+                {
+                    assert!(
+                        rejects(|| client.withdraw(&amount)),
+                        "withdraw must be rejected"
+                    );
+                    return;
+                }
+                client.withdraw(&amount);
+                self.model.contract_balance -= amount;
+                self.model.admin_balance += amount;
+            }
+            Op::Fund(amount) => {
+                // Not a contract call, only skip it when the admin can't pay
+                if self.model.admin_balance < amount {
+                    return;
+                }
+                self.payment_tkn.with_source_account(&self.admin).xfer(
+                    &Signature::Invoker,
+                    &0,
+                    &self.contract_identifier,
+                    &amount,
+                );
+                self.model.admin_balance -= amount;
+                self.model.contract_balance += amount;
+            }
+            Op::EnCshOut => {
+                let client = self.contract.with_source_account(&self.admin);
+                let valid = self.model.state != ModelState::CashOutEn
+                    && self.model.end_time != 0
+                    && self.model.now >= self.model.end_time
+                    && self.model.contract_balance >= self.model.price() * self.model.supply;
+                if !valid {
+                    assert!(
+                        rejects(|| client.en_csh_out()),
+                        "en_csh_out must be rejected"
+                    );
+                    return;
+                }
+                client.en_csh_out();
+                self.model.state = ModelState::CashOutEn;
+            }
+            Op::CashOut(user) => {
+                let client = self.contract.with_source_account(&self.users[user].0);
+                if self.model.state != ModelState::CashOutEn {
+                    assert!(rejects(|| client.cash_out()), "cash_out must be rejected");
+                    return;
+                }
+
+                let bonds = self.model.users[user].bonds;
+                let payout = bonds * self.model.price();
+                // The contract must never pay more than it holds
+                assert!(payout <= self.model.contract_balance);
+                client.cash_out();
+
+                let user_model = &mut self.model.users[user];
+                user_model.payment += payout;
+                user_model.bonds = 0;
+                self.model.supply -= bonds;
+                self.model.contract_balance -= payout;
+            }
+        }
+    }
+
+    fn check_invariants(&self) {
+        let mut holders = 0;
+        for (user, user_model) in self.users.iter().zip(self.model.users.iter()) {
+            let bonds = self.bond_tkn.balance(&user.1);
+            assert_eq!(bonds, user_model.bonds);
+            assert_eq!(self.payment_tkn.balance(&user.1), user_model.payment);
+            holders += bonds;
+        }
+
+        // Supply equals the sum of balances
+        assert_eq!(holders, self.model.supply);
+        assert_eq!(
+            self.bond_tkn.balance(&self.contract_identifier),
+            INITIAL_AMOUNT - self.model.sold
+        );
+
+        // The contract holds exactly what was paid in minus what was paid out
+        let contract_balance = self.payment_tkn.balance(&self.contract_identifier);
+        assert_eq!(contract_balance, self.model.contract_balance);
+        assert!(contract_balance >= 0);
+
+        assert_eq!(self.contract.get_price(), self.model.price());
+    }
+}
+
+fn run_lifecycle(lifecycle: &Lifecycle) {
+    let mut harness = Harness::new(lifecycle);
+    harness.check_invariants();
+
+    for op in lifecycle.ops.iter() {
+        harness.apply(op);
+        harness.check_invariants();
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn test_lifecycle_invariants(lifecycle in lifecycle_strategy()) {
+        run_lifecycle(&lifecycle);
+    }
+}
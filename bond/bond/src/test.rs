@@ -6,7 +6,7 @@ use soroban_sdk::testutils::{Accounts, Ledger, LedgerInfo};
 use soroban_sdk::{AccountId, BytesN, Env, IntoVal};
 
 use token::{Client as TokenClient, TokenMetadata};
-fn create_token_contract(
+pub(crate) fn create_token_contract(
     e: &Env,
     admin: &AccountId,
     name: &str,
@@ -28,7 +28,7 @@ fn create_token_contract(
     (id, token)
 }
 
-fn updates_contract_time(e: &Env, contract_id: BytesN<32>, time: u64) -> BondClient {
+pub(crate) fn updates_contract_time(e: &Env, contract_id: BytesN<32>, time: u64) -> BondClient {
     e.ledger().set(LedgerInfo {
         timestamp: time,
         protocol_version: 1,
@@ -39,11 +39,11 @@ fn updates_contract_time(e: &Env, contract_id: BytesN<32>, time: u64) -> BondCli
     return BondClient::new(&e, &contract_id);
 }
 
-fn days_to_seconds(days: u64) -> u64 {
+pub(crate) fn days_to_seconds(days: u64) -> u64 {
     days * 24 * 60 * 60
 }
 
-fn generate_user_account(
+pub(crate) fn generate_user_account(
     e: &Env,
     token_client: &TokenClient,
     token_admin: &AccountId,
@@ -686,3 +686,226 @@ fn test_enable_cash_out_when_not_enough_tokens() {
     // Try enable cash out
     contract.with_source_account(&admin).en_csh_out();
 }
+
+#[test]
+#[should_panic(expected = "Status(ContractError(13))")]
+fn test_buy_before_start_time() {
+    let e: Env = Default::default();
+
+    let admin = e.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let payment_tkn_admin = e.accounts().generate();
+
+    let (payment_tkn_id, payment_tkn) =
+        create_token_contract(&e, &payment_tkn_admin, &"USD Coin", &"USDC", 8);
+
+    let time = 0;
+    let contract_id = e.register_contract(None, Bond);
+    let contract = updates_contract_time(&e, contract_id.clone(), time);
+    let contract_identifier = Identifier::Contract(contract_id.clone());
+
+    let (user1, user1_id) = generate_user_account(
+        &e,
+        &payment_tkn,
+        &payment_tkn_admin,
+        &contract_identifier,
+        &100000,
+    );
+
+    // Initialize the contract
+    contract.initialize(
+        &admin_id.clone(),
+        &payment_tkn_id,
+        &"Bond".into_val(&e),
+        &"BND".into_val(&e),
+        &8,
+        &100,
+        &100, // 100 / 1000 = 0.1 => 10%
+        &30,
+        &InterestType::Compound,
+        &10000,
+    );
+
+    // Start the contract with an initial time 1 month from now
+    contract
+        .with_source_account(&admin)
+        .start(&days_to_seconds(30));
+    contract.with_source_account(&admin).add_user(&user1_id);
+
+    // Try to buy before the initial time
+    contract.with_source_account(&user1).buy(&200);
+}
+
+#[test]
+fn test_price_before_start_time() {
+    let e: Env = Default::default();
+
+    let admin = e.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let payment_tkn_admin = e.accounts().generate();
+
+    let (payment_tkn_id, payment_tkn) =
+        create_token_contract(&e, &payment_tkn_admin, &"USD Coin", &"USDC", 8);
+
+    let time = 0;
+    let contract_id = e.register_contract(None, Bond);
+    let mut contract = updates_contract_time(&e, contract_id.clone(), time);
+    let contract_identifier = Identifier::Contract(contract_id.clone());
+
+    let (user1, user1_id) = generate_user_account(
+        &e,
+        &payment_tkn,
+        &payment_tkn_admin,
+        &contract_identifier,
+        &100000,
+    );
+
+    // Initialize the contract
+    contract.initialize(
+        &admin_id.clone(),
+        &payment_tkn_id,
+        &"Bond".into_val(&e),
+        &"BND".into_val(&e),
+        &8,
+        &100,
+        &100, // 100 / 1000 = 0.1 => 10%
+        &30,
+        &InterestType::Compound,
+        &10000,
+    );
+
+    // Start the contract with an initial time 1 month from now
+    contract
+        .with_source_account(&admin)
+        .start(&days_to_seconds(30));
+    contract
+        .with_source_account(&admin)
+        .set_end(&days_to_seconds(10 * 30));
+    contract.with_source_account(&admin).add_user(&user1_id);
+
+    // The price is the initial price until the initial time
+    assert_eq!(100, contract.get_price());
+    contract = updates_contract_time(&e, contract_id.clone(), days_to_seconds(30));
+    assert_eq!(100, contract.get_price());
+
+    // User 1 buy 200 Bond tokens with price 100 at the initial time
+    contract.with_source_account(&user1).buy(&200);
+    assert_eq!(payment_tkn.balance(&user1_id), 80000);
+
+    // Update time in 2 months (1 month since the initial time)
+    contract = updates_contract_time(&e, contract_id.clone(), days_to_seconds(2 * 30));
+    assert_eq!(110, contract.get_price());
+}
+
+#[test]
+fn test_price_without_end_time() {
+    let e: Env = Default::default();
+
+    let admin = e.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let payment_tkn_admin = e.accounts().generate();
+
+    let (payment_tkn_id, _payment_tkn) =
+        create_token_contract(&e, &payment_tkn_admin, &"USD Coin", &"USDC", 8);
+
+    let contract_id = e.register_contract(None, Bond);
+    let mut contract = updates_contract_time(&e, contract_id.clone(), 10);
+
+    // Initialize the contract
+    contract.initialize(
+        &admin_id.clone(),
+        &payment_tkn_id,
+        &"Bond".into_val(&e),
+        &"BND".into_val(&e),
+        &8,
+        &100,
+        &100, // 100 / 1000 = 0.1 => 10%
+        &30,
+        &InterestType::Compound,
+        &10000,
+    );
+
+    // Start the contract without setting the end time
+    contract.with_source_account(&admin).start(&10);
+    assert_eq!(100, contract.get_price());
+
+    // The valuation is open-ended, update time in 2 months
+    contract = updates_contract_time(&e, contract_id.clone(), 10 + days_to_seconds(2 * 30));
+    assert_eq!(121, contract.get_price());
+}
+
+#[test]
+fn test_set_end_before_start() {
+    let e: Env = Default::default();
+
+    let admin = e.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let payment_tkn_admin = e.accounts().generate();
+
+    let (payment_tkn_id, _payment_tkn) =
+        create_token_contract(&e, &payment_tkn_admin, &"USD Coin", &"USDC", 8);
+
+    let contract_id = e.register_contract(None, Bond);
+    let mut contract = updates_contract_time(&e, contract_id.clone(), 0);
+
+    // Initialize the contract
+    contract.initialize(
+        &admin_id.clone(),
+        &payment_tkn_id,
+        &"Bond".into_val(&e),
+        &"BND".into_val(&e),
+        &8,
+        &100,
+        &100, // 100 / 1000 = 0.1 => 10%
+        &30,
+        &InterestType::Simple,
+        &10000,
+    );
+
+    // Set the end time before starting with a later initial time
+    contract
+        .with_source_account(&admin)
+        .set_end(&days_to_seconds(30));
+    contract
+        .with_source_account(&admin)
+        .start(&days_to_seconds(2 * 30));
+
+    // No interval is between the initial and the end time
+    contract = updates_contract_time(&e, contract_id.clone(), days_to_seconds(5 * 30));
+    assert_eq!(100, contract.get_price());
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(9))")]
+fn test_enable_cash_out_without_end_time() {
+    let e: Env = Default::default();
+
+    let admin = e.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let payment_tkn_admin = e.accounts().generate();
+
+    let (payment_tkn_id, _payment_tkn) =
+        create_token_contract(&e, &payment_tkn_admin, &"USD Coin", &"USDC", 8);
+
+    let contract_id = e.register_contract(None, Bond);
+    let contract = updates_contract_time(&e, contract_id.clone(), 20);
+
+    // Initialize the contract
+    contract.initialize(
+        &admin_id.clone(),
+        &payment_tkn_id,
+        &"Bond".into_val(&e),
+        &"BND".into_val(&e),
+        &8,
+        &100,
+        &100, // 100 / 1000 = 0.1 => 10%
+        &30,
+        &InterestType::Compound,
+        &10000,
+    );
+
+    // Start the contract without setting the end time
+    contract.with_source_account(&admin).start(&0);
+    // Try enable cash out
+    contract.with_source_account(&admin).en_csh_out();
+}
@@ -4,9 +4,14 @@ use std::env;
 use std::str;
 #[derive(Debug, Serialize, Deserialize)]
 struct CarData {
+    category: Vec<u8>,
     color: Vec<u8>,
     horse: u8,
+    location: Vec<u8>,
+    maint: bool,
+    mileage: u64,
     model: Vec<u8>,
+    year: u32,
 }
 
 fn main() -> Result<()> {
@@ -18,6 +23,11 @@ fn main() -> Result<()> {
     println!("Model: {}", str::from_utf8(&data.model).unwrap());
     println!("Color: {}", str::from_utf8(&data.color).unwrap());
     println!("Horse: {}", data.horse);
+    println!("Year: {}", data.year);
+    println!("Mileage: {}", data.mileage);
+    println!("Category: {}", str::from_utf8(&data.category).unwrap());
+    println!("Location: {}", str::from_utf8(&data.location).unwrap());
+    println!("Maintenance: {}", data.maint);
 
     Ok(())
 }
@@ -1,26 +1,94 @@
 use crate::contract_trait::CarRentalTrait;
 use crate::errors::Error;
+use crate::event;
 use crate::metadata::{
-    check_admin, has_admin, has_car, has_rented_car, is_client_authorized, read_admin, read_car,
-    read_client, read_nonce, read_rented_car, remove_car, remove_rented_car,
-    verify_and_consume_nonce, write_admin, write_car, write_client, write_rented_car,
+    add_insurance, add_pending, add_rental, add_service, check_role, has_admin, has_branch,
+    has_car, has_rented_car, has_role, is_client_authorized, move_car, read_active, read_admin,
+    read_approval_expiry, read_approval_ttl, read_auto_approval, read_booked, read_bookings,
+    read_branch, read_branches, read_car, read_car_history, read_car_rating, read_client,
+    read_client_history, read_insurance, read_loyalty, read_maint_wins, read_nonce, read_owed,
+    read_pending, read_plates, read_policy, read_pool, read_rental, read_rented_car,
+    read_reputation, read_roles, read_seasons, read_services, read_surges, read_svc_rule,
+    read_svc_state, read_tier, read_tier_limit, read_token, read_weekend, remove_branch,
+    remove_car, remove_rented_car, verify_and_consume_nonce, write_admin, write_approval_expiry,
+    write_approval_ttl, write_auto_approval, write_bookings, write_branch, write_car,
+    write_car_rating, write_client, write_loyalty, write_maint_wins, write_owed, write_policy,
+    write_pool, write_rental, write_rented_car, write_reputation, write_roles, write_seasons,
+    write_surges, write_svc_rule, write_svc_state, write_tier, write_tier_limit, write_token,
+    write_weekend,
+};
+use crate::storage_types::{
+    AutoAppr, Booking, CarDataKey, CarInfo, CarSpec, CarStatus, ClientStatus, Denial, Insurance,
+    Invoice, Loyalty, MaintWin, Outcome, PendReq, Pricing, Quote, Rating, RentalRec,
+    RentedCarDataKey, RentedCarStatus, Reputation, ResvPolicy, Role, Season, ServiceRec, Surge,
+    SvcRule, SvcState,
 };
-use crate::storage_types::{CarDataKey, ClientStatus, RentedCarDataKey, RentedCarStatus};
 use soroban_auth::verify;
 use soroban_auth::{Identifier, Signature};
-use soroban_sdk::{contractimpl, panic_with_error, symbol, BigInt, Bytes, Env};
+use soroban_sdk::{
+    contractimpl, panic_with_error, symbol, Bytes, BytesN, Env, IntoVal, RawVal, TryFromVal, Vec,
+};
+
+const DAY_IN_SECONDS: u64 = 24 * 60 * 60;
+const HOUR_IN_SECONDS: u64 = 60 * 60;
+// multipliers of the pricing rules are in basis points
+const BASIS_POINTS: u32 = 10000;
+// max length of a canonical plate
+const PLATE_MAX_LEN: u32 = 10;
+
+pub mod token {
+    soroban_sdk::contractimport!(file = "../soroban_token_spec.wasm");
+}
 pub struct CarRentalContract;
 
 /*
 How to use this contract to rent a car
-1. call initialize to set the admin
-2. admin is able to call add_car to add a car to the contract. Or remove_car to remove a previous added car
-3. client open a request to be able to rent a car (open_req)
-4. admin approve or decline the client request (appr_req, deny_req). Client need to be approved to reserve a car
-4. client reserve a car (resrve_car) if the car exist in the contract
-5. client take the car (take_car) if previous reserved by the same client
-6. client drop the car (drop_car) if previous taken. Change the status of the car to DropReview
-7. admin accept or deny the drop (accpt_drop, deny_drop). if deny_drop it will change the status to DropReviewDenied. Client needs to call drop_car again again
+1. call initialize to set the admin and the token used for payments
+   the admin is the owner of the contract and can grant (grant_role) or revoke (revk_role) the operator roles:
+   FleetManager (add_car, update_car, set_maint, remove_car, set_late, set_usage, set_branch, rm_branch, reloc_car, add_maint, rm_maint, svc_done, set_svc, add_insur, set_wkend, add_season, rm_season, set_surge), ClientApprover (appr_req, deny_req, suspend, reinstate, revoke, set_tier, set_auto, batch_req), ReturnInspector (accpt_drop, deny_drop, score_ret)
+   and Arbiter (settle).
+   The owner has all the roles but Arbiter, an operator can't be both ReturnInspector and Arbiter, and only the owner can change the admin (set_admin), the reservation policy (set_policy), the approval validity (set_valid),
+   the limit of cars reserved or rented at the same time by the clients of each tier (set_limit), the loyalty discounts (set_loyal)
+   and the migration of the plates (migr_plate)
+2. admin (or a FleetManager) is able to call add_car to add a car to the contract, with its daily rate and security deposit. Or remove_car to remove a previous added car
+   Plates are canonicalized by every call: upper case letters and digits, without separators ("abc-1234" is the plate ABC1234).
+   The owner moves a car added with a non canonical plate to its canonical plate (migr_plate)
+   The car data can be changed (update_car) while the car is not rented, and a car in maintenance (set_maint) can't be reserved
+   Every car has a home branch and a current branch. The branches are added with the relocation fee of the one-way rentals returned there (set_branch)
+   and removed (rm_branch) when no car, reservation or booking uses them. A car is moved between branches while not rented (reloc_car)
+   Maintenance windows (add_maint, rm_maint) take a car out of service in a period: reservations and bookings can't overlap them.
+   The completed services (svc_done) are kept in the service history of the car (svc_hist) with the mileage and the cost,
+   and a car is flagged as due for service (svc_state) after the rentals or km since its last service set by the rule (set_svc)
+3. client open a request to be able to rent a car (open_req), optionally with the hash of the KYC documents. The request waits in the pending queue (pending)
+4. admin (or a ClientApprover) approve or decline the client request (appr_req, deny_req), or several requests at once (batch_req). Client need to be approved to reserve a car
+   The approval expires after the validity period (set_valid), and the client opens a new request
+   An approved client can be suspended (suspend) until reinstated (reinstate), or revoked (revoke). They can't reserve a car, but can finish the current rentals
+   A client with a good reputation (average return score, set_auto) is approved by open_req
+4. client reserve a car (resrve_car) for a number of days if the car exist in the contract. The deposit is taken into escrow.
+   The rental price is quoted at the reservation (quote) with the pricing rules: weekend and seasonal multipliers of the daily rate
+   and a surge multiplier by the share of reserved or rented cars
+   The reservation expires after the policy ttl (set_policy). The client can cancel it (cancl_resv), and an expired reservation
+   can be released by anyone (rls_expird) or taken over by the next resrve_car. A cancellation fee can be withheld from the deposit
+   The client can also book a car for future periods (book_car) that don't overlap other bookings, and cancel them (cancl_book).
+   A booking not taken until the end of the period is released (rls_expird) like an expired reservation
+   The client can buy an insurance product (registered with add_insur) at the reservation or booking. The premium goes to the claims pool
+   and is refunded if the car is not taken (cancelled or expired)
+   The car is picked up at a branch and can be dropped off at another one (one-way rental), paying the relocation fee of the drop-off branch.
+   The available cars can be listed by branch (list_avail)
+5. client take the car (take_car) at the pickup branch if previous reserved by the same client, or booked for the current period. The client signs the odometer and fuel level at the pickup. The quoted rental and the relocation fee are charged and the car must be returned in days from now
+6. client drop the car (drop_car) if previous taken, reporting the odometer and fuel level. Change the status of the car to DropReview. A late fee is computed if the agreed return time plus the grace period has passed
+7. admin (or a ReturnInspector) accept or deny the drop (accpt_drop, deny_drop). accpt_drop confirms the odometer and fuel level, charges the late fee, the km above the allowance and the missing fuel, and refunds the deposit.
+   The charges the client account can't pay are owed (owed), and the client pays them (pay_owed) before reserving again. The car stays at the drop-off branch. if deny_drop it will change the status to DropReviewDenied with a damage report (reason, estimated cost and evidence hash)
+8. client accept the damage report (accpt_dmg), and the cost is withheld from the deposit, or dispute it (dispute). An arbiter settles the dispute (settle) withholding up to the cost.
+   In both cases the car goes back to DropReview and the drop can be accepted
+9. after the drop is accepted, the inspector scores the return (score_ret) and the client rates the car and the service (rate_rent).
+   The return scores build the client reputation, and the completed rentals give loyalty discounts at the reservation
+   For an insured rental the claims pool pays the damages above the deductible, and only the rest is withheld from the deposit
+
+Every signed call (admin or client) signs the contract id, the function name and all the arguments of the call, starting with
+the signer identifier and the nonce, e.g. resrve_car signs (client, nonce, plate, days, insurance, dropoff). A signature can't be reused with other arguments.
+
+Payments are done with the token informed in init. The client must approve the contract (token approve) to transfer the deposit and the rental.
 */
 
 #[contractimpl]
@@ -28,30 +96,35 @@ impl CarRentalTrait for CarRentalContract {
     // Initialized the contract. Set the contract Admin.
     // Executed only one time.
     // Necessary to inform the public key of the admin.
-    // After initialized, only this admin can execute the functions that contains ADMIN EXPECTED.
-    fn init(env: Env, admin: Identifier) {
+    // After initialized, only this admin (the owner) or the operators with the role informed can execute the functions that contains ADMIN EXPECTED.
+    fn init(env: Env, admin: Identifier, token_id: BytesN<32>) {
         // Check if the contract is already initialized and admin already set.
         if has_admin(&env) {
             panic_with_error!(&env, Error::AlreadyInit)
         }
         // write admin inside the contract storage.
         write_admin(&env, admin);
+        // write the payment token inside the contract storage.
+        write_token(&env, token_id);
+        // write the default reservation policy: one day to take the car and one hour to cancel without fee.
+        write_policy(
+            &env,
+            ResvPolicy {
+                ttl: DAY_IN_SECONDS,
+                free: HOUR_IN_SECONDS,
+                fee: 0,
+            },
+        );
     }
 
     // Add a new car to the contract.
-    // ADMIN EXPECTED
-    // Necessary to inform: the plate (str), model (str), color (str) and horse power (int) of the car and the nonce (int).
-    fn add_car(
-        env: Env,
-        admin: Signature,
-        nonce: BigInt,
-        plate: Bytes,
-        model: Bytes,
-        color: Bytes,
-        horse: i32,
-    ) {
-        // verify if admin informed is the same admin that initialized the contract.
-        check_admin(&env, &admin);
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary to inform: the plate (str), the car (object with model (str), color (str), horse power (int), year (int),
+    // mileage (int), category (str), home branch (str), daily rate (int) and deposit (int)) and the nonce (int).
+    // The daily rate and the deposit are amounts of the payment token. The car starts at its home branch (set_branch).
+    fn add_car(env: Env, admin: Signature, nonce: i128, plate: Bytes, car: CarSpec) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
         // consume the nonce to avoid replay call contract.
         verify_and_consume_nonce(&env, &admin, &nonce);
 
@@ -59,31 +132,147 @@ impl CarRentalTrait for CarRentalContract {
         let admin_id = admin.identifier(&env);
 
         // verify the signature of the admin to that specific call.
-        verify(&env, &admin, symbol!("add_car"), (admin_id, nonce));
+        verify(
+            &env,
+            &admin,
+            symbol!("add_car"),
+            (admin_id, nonce, &plate, &car),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
 
         // check if the car has already been added based on the plate.
         if has_car(&env, &plate) {
             panic_with_error!(&env, Error::CarAlreadyExists)
         }
 
+        // amounts can't be negative
+        if car.daily_rate < 0 || car.deposit < 0 {
+            panic_with_error!(&env, Error::InvalidAmount)
+        }
+        // the home branch must be registered
+        if !has_branch(&env, &car.home) {
+            panic_with_error!(&env, Error::BranchNotExists)
+        }
+
         // write the car inside the contract storage.
-        write_car(
+        let car_data = CarDataKey {
+            model: car.model,
+            color: car.color,
+            horse: car.horse,
+            year: car.year,
+            mileage: car.mileage,
+            category: car.category,
+            location: car.home.clone(),
+            home: car.home,
+            daily_rate: car.daily_rate,
+            deposit: car.deposit,
+            grace: 0,
+            late_rate: 0,
+            maint: false,
+            km_rate: 0,
+            km_day: 0,
+            fuel_rate: 0,
+        };
+        write_car(&env, &plate, car_data.clone());
+        event::add_car(&env, plate, car_data);
+    }
+
+    // Update the data of a car.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary to inform the same data of add_car. The late fee settings, the maintenance flag and the current branch are kept.
+    // The car can't be reserved or rented.
+    fn update_car(env: Env, admin: Signature, nonce: i128, plate: Bytes, car: CarSpec) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
             &env,
-            &plate,
-            CarDataKey {
-                model: model,
-                color: color,
-                horse: horse,
-            },
-        )
+            &admin,
+            symbol!("update_car"),
+            (admin_id, nonce, &plate, &car),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        // panic if car not exist in the contract storage
+        if !has_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarNotExists)
+        }
+
+        // panic if the car is reserved or rented. The rental uses the car data until it is closed
+        if has_rented_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarAlreadyRented)
+        }
+
+        // amounts can't be negative
+        if car.daily_rate < 0 || car.deposit < 0 {
+            panic_with_error!(&env, Error::InvalidAmount)
+        }
+        // the home branch must be registered
+        if !has_branch(&env, &car.home) {
+            panic_with_error!(&env, Error::BranchNotExists)
+        }
+
+        let mut car_data = read_car(&env, plate.clone());
+        car_data.model = car.model;
+        car_data.color = car.color;
+        car_data.horse = car.horse;
+        car_data.year = car.year;
+        car_data.mileage = car.mileage;
+        car_data.category = car.category;
+        car_data.home = car.home;
+        car_data.daily_rate = car.daily_rate;
+        car_data.deposit = car.deposit;
+        write_car(&env, &plate, car_data.clone());
+        event::update_car(&env, plate, car_data);
+    }
+
+    // Put a car in (or take it out of) maintenance.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary to inform the plate (str), the maintenance flag (bool) and the nonce (int).
+    // A car in maintenance can't be reserved. A current rental is not affected.
+    fn set_maint(env: Env, admin: Signature, nonce: i128, plate: Bytes, maint: bool) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("set_maint"),
+            (admin_id, nonce, &plate, maint),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        // panic if car not exist in the contract storage
+        if !has_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarNotExists)
+        }
+
+        let mut car_data = read_car(&env, plate.clone());
+        car_data.maint = maint;
+        write_car(&env, &plate, car_data);
+        event::set_maint(&env, plate, maint);
     }
 
     // Remove the car from the storage based on the plate(str).
-    // ADMIN EXPECTED
+    // ADMIN EXPECTED (FleetManager role)
     // Necessary inform the plate (str) and the nonce (int) to avoid replay call.
-    fn remove_car(env: Env, admin: Signature, nonce: BigInt, plate: Bytes) {
-        // verify if admin informed is the same admin that initialized the contract.
-        check_admin(&env, &admin);
+    fn remove_car(env: Env, admin: Signature, nonce: i128, plate: Bytes) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
         // consume the nonce to avoid replay call contract.
         verify_and_consume_nonce(&env, &admin, &nonce);
 
@@ -91,7 +280,20 @@ impl CarRentalTrait for CarRentalContract {
         let admin_id = admin.identifier(&env);
 
         // verify the signature of the admin to that specific call.
-        verify(&env, &admin, symbol!("remove_car"), (admin_id, nonce));
+        verify(
+            &env,
+            &admin,
+            symbol!("remove_car"),
+            (admin_id, nonce, &plate),
+        );
+
+        // a car added before the plate canonicalization is found by the plate as it was stored,
+        // the other cars by the canonical plate (upper case, without separators).
+        let plate = if has_car(&env, &plate) {
+            plate
+        } else {
+            canonical_plate(&env, &plate)
+        };
 
         // panic if car not exist in the contract storage
         if !has_car(&env, &plate) {
@@ -102,284 +304,2975 @@ impl CarRentalTrait for CarRentalContract {
         if has_rented_car(&env, &plate) {
             panic_with_error!(&env, Error::CarAlreadyRented)
         }
+        // panic if the car is booked. The deposits of the bookings are in escrow
+        if !read_bookings(&env, &plate).is_empty() {
+            panic_with_error!(&env, Error::CarAlreadyRented)
+        }
 
         // remove the car from the contract storage. Necessary car not be rented.
-        remove_car(&env, &plate)
+        remove_car(&env, &plate);
+        event::remove_car(&env, plate);
     }
 
-    // Approved a client request to be allowed to rent a car.
-    // ADMIN EXPECTED
-    // Necessary the client public key
-    fn appr_req(env: Env, admin: Signature, client: Identifier, nonce: BigInt) {
-        // verify if admin informed is the same admin that initialized the contract.
-        check_admin(&env, &admin);
+    // Move a car added before the plate canonicalization to its canonical plate,
+    // with its rental, bookings, history and ratings.
+    // ADMIN EXPECTED (Owner role)
+    // Necessary the plate (str) as it was added and the nonce (int).
+    fn migr_plate(env: Env, admin: Signature, nonce: i128, plate: Bytes) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::Owner);
         // consume the nonce to avoid replay call contract.
         verify_and_consume_nonce(&env, &admin, &nonce);
 
         // get the admin identifier based on the admin signature.
         let admin_id = admin.identifier(&env);
+
         // verify the signature of the admin to that specific call.
-        verify(&env, &admin, symbol!("appr_req"), (admin_id, nonce));
+        verify(
+            &env,
+            &admin,
+            symbol!("migr_plate"),
+            (admin_id, nonce, &plate),
+        );
+
+        // the plate is looked up as stored, without canonicalization
+        if !has_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarNotExists)
+        }
+        let canonical = legacy_canonical_plate(&env, &plate);
+        if canonical == plate {
+            return;
+        }
+        // the canonical plate is already used by another car, one of them must be removed first
+        if has_car(&env, &canonical) {
+            panic_with_error!(&env, Error::CarAlreadyExists)
+        }
 
-        // write the client status Approved to this specific client.
-        write_client(&env, client, ClientStatus::Approved)
+        move_car(&env, &plate, &canonical);
+        event::migr_plate(&env, plate, canonical);
     }
 
-    // Deny a client request to be allowed to rent a car.
-    // ADMIN EXPECTED
-    // Necessary the client public key
-    fn deny_req(env: Env, admin: Signature, client: Identifier, nonce: BigInt) {
-        // verify if admin informed is the same admin that initialized the contract.
-        check_admin(&env, &admin);
+    // Set the late return policy of a car.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the car plate (str), nonce (int), grace period in seconds (int) and late fee per hour (int) charged after the grace period.
+    fn set_late(
+        env: Env,
+        admin: Signature,
+        nonce: i128,
+        plate: Bytes,
+        grace: u64,
+        late_rate: i128,
+    ) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
         // consume the nonce to avoid replay call contract.
         verify_and_consume_nonce(&env, &admin, &nonce);
         // get the admin identifier based on the admin signature.
         let admin_id = admin.identifier(&env);
         // verify the signature of the admin to that specific call.
-        verify(&env, &admin, symbol!("deny_req"), (admin_id, nonce));
-        // write the client status Declined to this specific client.
-        write_client(&env, client, ClientStatus::Declined)
+        verify(
+            &env,
+            &admin,
+            symbol!("set_late"),
+            (admin_id, nonce, &plate, grace, late_rate),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        // panic if car not exist in the contract storage
+        if !has_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarNotExists)
+        }
+
+        // the late fee can't be negative
+        if late_rate < 0 {
+            panic_with_error!(&env, Error::InvalidAmount)
+        }
+
+        let mut car_data = read_car(&env, plate.clone());
+        car_data.grace = grace;
+        car_data.late_rate = late_rate;
+        write_car(&env, &plate, car_data);
+        event::set_late(&env, plate, grace, late_rate);
     }
 
-    // Accept the client drop car
-    // ADMIN EXPECTED
-    // Necessary the car plate (str), nonce (int).
-    fn accpt_drop(env: Env, admin: Signature, nonce: BigInt, plate: Bytes) {
-        // verify if admin informed is the same admin that initialized the contract.
-        check_admin(&env, &admin);
+    // Set the usage pricing of a car.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the car plate (str), nonce (int), fee per km above the allowance (int), mileage allowance per rental day in km (int)
+    // and fee per fuel level point (%) missing at the return (int).
+    fn set_usage(
+        env: Env,
+        admin: Signature,
+        nonce: i128,
+        plate: Bytes,
+        km_rate: i128,
+        km_day: u64,
+        fuel_rate: i128,
+    ) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
         // consume the nonce to avoid replay call contract.
         verify_and_consume_nonce(&env, &admin, &nonce);
         // get the admin identifier based on the admin signature.
         let admin_id = admin.identifier(&env);
         // verify the signature of the admin to that specific call.
-        verify(&env, &admin, symbol!("accpt_drop"), (admin_id, nonce));
+        verify(
+            &env,
+            &admin,
+            symbol!("set_usage"),
+            (admin_id, nonce, &plate, km_rate, km_day, fuel_rate),
+        );
 
-        //  check if the car was rented
-        if !has_rented_car(&env, &plate) {
-            panic_with_error!(&env, Error::CarIsNotRented)
-        }
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
 
-        //  get the rended car data
-        let rented_car_data = read_rented_car(&env, &plate);
+        // panic if car not exist in the contract storage
+        if !has_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarNotExists)
+        }
 
-        // check if the car status is in drop review. If not it is not requested to drop
-        if rented_car_data.status != RentedCarStatus::DropReview {
-            panic_with_error!(&env, Error::CarIsNotInDropReview)
+        // the fees can't be negative
+        if km_rate < 0 || fuel_rate < 0 {
+            panic_with_error!(&env, Error::InvalidAmount)
         }
 
-        // remove the rented car from the contract storage.
-        // The car is not rented anymore by any client.
-        remove_rented_car(&env, &plate)
+        let mut car_data = read_car(&env, plate.clone());
+        car_data.km_rate = km_rate;
+        car_data.km_day = km_day;
+        car_data.fuel_rate = fuel_rate;
+        write_car(&env, &plate, car_data);
+        event::set_usage(&env, plate, km_rate, km_day, fuel_rate);
     }
 
-    // Deny the client drop car
-    // ADMIN EXPECTED
-    // Necessary the car plate (str), nonce (int).
-    fn deny_drop(env: Env, admin: Signature, nonce: BigInt, plate: Bytes) {
-        // verify if admin informed is the same admin that initialized the contract.
-        check_admin(&env, &admin);
+    // Add a branch or change its relocation fee.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the nonce (int), the branch code (str) and the relocation fee (int) of the one-way rentals returned at the branch.
+    fn set_branch(env: Env, admin: Signature, nonce: i128, branch: Bytes, reloc: i128) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
         // consume the nonce to avoid replay call contract.
         verify_and_consume_nonce(&env, &admin, &nonce);
         // get the admin identifier based on the admin signature.
         let admin_id = admin.identifier(&env);
         // verify the signature of the admin to that specific call.
-        verify(&env, &admin, symbol!("accpt_drop"), (admin_id, nonce));
+        verify(
+            &env,
+            &admin,
+            symbol!("set_branch"),
+            (admin_id, nonce, &branch, reloc),
+        );
 
-        // check if car is rented
-        if !has_rented_car(&env, &plate) {
-            panic_with_error!(&env, Error::CarIsNotRented)
+        // the branch needs a code and the fee can't be negative
+        if branch.is_empty() {
+            panic_with_error!(&env, Error::BranchNotExists)
         }
-
-        // get the rented car data
-        let mut rented_car_data = read_rented_car(&env, &plate);
-
-        // verify if car status id DropReview. If not it is not requested to drop
-        if rented_car_data.status != RentedCarStatus::DropReview {
-            panic_with_error!(&env, Error::CarIsNotInDropReview)
+        if reloc < 0 {
+            panic_with_error!(&env, Error::InvalidAmount)
         }
 
-        // change the rented car status to DropReviewDenied
-        rented_car_data.status = RentedCarStatus::DropReviewDenied;
-        // write the rented car data to the contract storage
-        write_rented_car(&env, &plate, rented_car_data)
+        write_branch(&env, &branch, reloc);
+        event::set_branch(&env, branch, reloc);
     }
-    // Change the admin for this contract
-    // ADMIN EXPECTED
-    // Necessary the new_admin public key, nonce (int), new admin signature (str).
-    fn set_admin(env: Env, admin: Signature, nonce: BigInt, new_admin: Identifier) {
-        // verify if admin informed is the same admin that initialized the contract.
-        check_admin(&env, &admin);
+
+    // Remove a branch.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the nonce (int) and the branch code (str).
+    // The branch can't be the home or current branch of a car, or the pickup or drop-off branch of a reservation or booking.
+    fn rm_branch(env: Env, admin: Signature, nonce: i128, branch: Bytes) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
         // consume the nonce to avoid replay call contract.
         verify_and_consume_nonce(&env, &admin, &nonce);
         // get the admin identifier based on the admin signature.
         let admin_id = admin.identifier(&env);
         // verify the signature of the admin to that specific call.
-        verify(&env, &admin, symbol!("set_admin"), (admin_id, nonce));
-        // write the new_admin to the contract storage
-        write_admin(&env, new_admin);
-    }
-
-    // Client open request to be able to reserve and rent a caker
-    // Necessary the client public key, nonce (int).
-    fn open_req(env: Env, client: Signature, nonce: BigInt) {
-        // consume the nonce to avoid replay call contract.
-        verify_and_consume_nonce(&env, &client, &nonce);
-        // get the client identifier based on the client signature.
-        let client_identifier = client.identifier(&env);
         verify(
             &env,
-            &client,
-            symbol!("open_req"),
-            (&client_identifier, nonce),
+            &admin,
+            symbol!("rm_branch"),
+            (admin_id, nonce, &branch),
         );
-        // write the client status Pending to this specific client in the storage
-        write_client(&env, client_identifier, ClientStatus::Pending)
+
+        if !has_branch(&env, &branch) {
+            panic_with_error!(&env, Error::BranchNotExists)
+        }
+        if is_branch_in_use(&env, &branch) {
+            panic_with_error!(&env, Error::BranchInUse)
+        }
+
+        remove_branch(&env, &branch);
+        event::rm_branch(&env, branch);
     }
 
-    // Client reserve a car to be able to take it later
-    // Necessary the client public key, nonce (int), car plate (str).
-    fn resrve_car(env: Env, client: Signature, nonce: BigInt, plate: Bytes) {
+    // Move a car to another branch, e.g. back to its home branch after a one-way rental.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the nonce (int), the car plate (str) and the branch code (str). The car can't be reserved or rented.
+    fn reloc_car(env: Env, admin: Signature, nonce: i128, plate: Bytes, branch: Bytes) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
         // consume the nonce to avoid replay call contract.
-        verify_and_consume_nonce(&env, &client, &nonce);
-        // get the client identifier based on the client signature.
-        let client_identifier = client.identifier(&env);
-        // verify the signature of the client to that specific call.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
         verify(
             &env,
-            &client,
-            symbol!("resrve_car"),
-            (&client_identifier, nonce),
+            &admin,
+            symbol!("reloc_car"),
+            (admin_id, nonce, &plate, &branch),
         );
 
-        // check if the client is authorized to reserve a car (client status approved)
-        if !is_client_authorized(&env, &client_identifier) {
-            panic_with_error!(&env, Error::ClientNotAuthorized)
-        }
-        // check if the car exists in the contract storage
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        // panic if car not exist in the contract storage
         if !has_car(&env, &plate) {
             panic_with_error!(&env, Error::CarNotExists)
         }
-
-        // check if the car is already rented
+        // the car is with the client while reserved or rented
         if has_rented_car(&env, &plate) {
             panic_with_error!(&env, Error::CarAlreadyRented)
         }
+        if !has_branch(&env, &branch) {
+            panic_with_error!(&env, Error::BranchNotExists)
+        }
 
-        // write the rented car data to the contract storage with status Reserved
-        write_rented_car(
-            &env,
-            &plate,
-            RentedCarDataKey {
-                renter: client_identifier,
-                status: RentedCarStatus::Reserved,
-            },
-        )
+        let mut car_data = read_car(&env, plate.clone());
+        car_data.location = branch.clone();
+        write_car(&env, &plate, car_data);
+        event::reloc_car(&env, plate, branch);
     }
 
-    // Client take a car (previous reserved)
-    // Necessary the client public key, nonce (int), car plate (str).
-    fn take_car(env: Env, client: Signature, nonce: BigInt, plate: Bytes) {
+    // Schedule a maintenance window: the car is out of service in the period [start, end).
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the nonce (int), the car plate (str), start and end (int) timestamps of the period.
+    // The window can't overlap the other windows, the bookings or the current rental of the car.
+    fn add_maint(env: Env, admin: Signature, nonce: i128, plate: Bytes, start: u64, end: u64) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
         // consume the nonce to avoid replay call contract.
-        verify_and_consume_nonce(&env, &client, &nonce);
-        // get the client identifier based on the client signature.
-        let client_identifier = client.identifier(&env);
-        // verify the signature of the client to that specific call.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
         verify(
             &env,
-            &client,
-            symbol!("take_car"),
-            (&client_identifier, nonce),
+            &admin,
+            symbol!("add_maint"),
+            (admin_id, nonce, &plate, start, end),
         );
 
-        // check if car is reserved
-        if !has_rented_car(&env, &plate) {
-            panic_with_error!(&env, Error::CarIsNotRented)
-        }
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
 
-        // get car data from the contract storage
-        let mut rented_car_data = read_rented_car(&env, &plate);
-        //  check if the renter is the same client that are requesting to take the car
-        if rented_car_data.renter != client_identifier {
-            panic_with_error!(&env, Error::ClientIsNotRenter)
+        // panic if car not exist in the contract storage
+        if !has_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarNotExists)
         }
-        // check if the car status is Reserved. If not it is not possible to take the car
-        if rented_car_data.status != RentedCarStatus::Reserved {
-            panic_with_error!(&env, Error::CarIsNotReserved)
+        // the period can't be in the past
+        let now = env.ledger().timestamp();
+        if end <= start || end <= now {
+            panic_with_error!(&env, Error::InvalidDuration)
+        }
+        if !is_period_free(&env, &plate, start, end) {
+            panic_with_error!(&env, Error::BookingOverlap)
         }
 
-        // change car status to rented
-        rented_car_data.status = RentedCarStatus::Rented;
-        // write the rented car data to the contract storage
-        write_rented_car(&env, &plate, rented_car_data)
+        // keep the windows sorted by the start of the period, without the ended ones
+        let mut windows = Vec::new(&env);
+        let mut added = false;
+        for window in read_maint_wins(&env, &plate).iter() {
+            let window = window.unwrap();
+            if window.end <= now {
+                continue;
+            }
+            if start < window.end && window.start < end {
+                panic_with_error!(&env, Error::MaintOverlap)
+            }
+            if start < window.start && !added {
+                windows.push_back(MaintWin { start, end });
+                added = true;
+            }
+            windows.push_back(window);
+        }
+        if !added {
+            windows.push_back(MaintWin { start, end });
+        }
+        write_maint_wins(&env, &plate, windows);
+        event::add_maint(&env, plate, start, end);
     }
 
-    // Client request to drop the car
-    // Necessary the client public key, nonce (int), car plate (str).
-    fn drop_car(env: Env, client: Signature, nonce: BigInt, plate: Bytes) {
+    // Cancel the maintenance window of the car that starts at start.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the nonce (int), the car plate (str) and the start (int) timestamp of the window.
+    fn rm_maint(env: Env, admin: Signature, nonce: i128, plate: Bytes, start: u64) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
         // consume the nonce to avoid replay call contract.
-        verify_and_consume_nonce(&env, &client, &nonce);
-        // get the client identifier based on the client signature.
-        let client_identifier = client.identifier(&env);
-        // verify the signature of the client to that specific call.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
         verify(
             &env,
-            &client,
-            symbol!("drop_car"),
-            (&client_identifier, nonce),
+            &admin,
+            symbol!("rm_maint"),
+            (admin_id, nonce, &plate, start),
         );
 
-        // check if car is rented
-        if !has_rented_car(&env, &plate) {
-            panic_with_error!(&env, Error::CarIsNotRented)
-        }
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
 
-        // get car data from the contract storage
-        let mut rented_car_data = read_rented_car(&env, &plate);
-        // check if the renter is the same client that are requesting to drop the car
-        if rented_car_data.renter != client_identifier {
-            panic_with_error!(&env, Error::ClientIsNotRenter)
-        }
-        // check if the car status is Rented or DropReviewDenied. If not it is not possible to drop the car
-        // client can request the drop_car if has already been denied. It's possible to retry
-        match rented_car_data.status {
-            RentedCarStatus::Rented | RentedCarStatus::DropReviewDenied => {
-                // change car status to DropReview
-                rented_car_data.status = RentedCarStatus::DropReview;
-                // write the rented car data to the contract storage
-                write_rented_car(&env, &plate, rented_car_data)
+        let mut windows = read_maint_wins(&env, &plate);
+        let mut index = None;
+        for (i, window) in windows.iter().enumerate() {
+            if window.unwrap().start == start {
+                index = Some(i as u32);
+                break;
             }
-            _ => panic_with_error!(&env, Error::CarIsNotWithRentedStatus),
         }
+        match index {
+            Some(i) => windows.remove(i),
+            None => panic_with_error!(&env, Error::MaintNotExists),
+        };
+        write_maint_wins(&env, &plate, windows);
+        event::rm_maint(&env, plate, start);
     }
 
-    // return the client status
-    fn read_clnt(env: Env, client: Identifier) -> ClientStatus {
-        read_client(&env, client)
-    }
-
-    // return the nonce from this contract execution
-    fn nonce(env: Env, identifier: Identifier) -> BigInt {
-        read_nonce(&env, &identifier)
-    }
-
-    // return the car data
+    // Record a completed service of the car.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the nonce (int), the car plate (str), the odometer (int) at the service and its cost (int).
+    // The car can't be reserved or rented. The usage since the last service is reset
+    // and the maintenance windows already started are closed.
+    fn svc_done(env: Env, admin: Signature, nonce: i128, plate: Bytes, mileage: u64, cost: i128) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("svc_done"),
+            (admin_id, nonce, &plate, mileage, cost),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        // panic if car not exist in the contract storage
+        if !has_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarNotExists)
+        }
+        // the car is with the client while reserved or rented
+        if has_rented_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarAlreadyRented)
+        }
+        // the odometer can't be lower than the car mileage
+        let mut car_data = read_car(&env, plate.clone());
+        if mileage < car_data.mileage {
+            panic_with_error!(&env, Error::InvalidReading)
+        }
+        if cost < 0 {
+            panic_with_error!(&env, Error::InvalidAmount)
+        }
+
+        let now = env.ledger().timestamp();
+        let state = read_svc_state(&env, &plate);
+        add_service(
+            &env,
+            &plate,
+            ServiceRec {
+                done: now,
+                mileage,
+                cost,
+                rentals: state.rentals,
+                km: state.km,
+            },
+        );
+        write_svc_state(
+            &env,
+            &plate,
+            SvcState {
+                rentals: 0,
+                km: 0,
+                due: false,
+            },
+        );
+        car_data.mileage = mileage;
+        write_car(&env, &plate, car_data);
+
+        // the windows already started are done with the service
+        let mut windows = Vec::new(&env);
+        for window in read_maint_wins(&env, &plate).iter() {
+            let window = window.unwrap();
+            if window.start > now {
+                windows.push_back(window);
+            }
+        }
+        write_maint_wins(&env, &plate, windows);
+        event::svc_done(&env, plate, mileage, cost);
+    }
+
+    // Set the service rule: a car is due for service after the number of completed rentals
+    // or the km driven since its last service.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the nonce (int), the number of rentals (int) and the km (int), 0 for no limit.
+    fn set_svc(env: Env, admin: Signature, nonce: i128, rentals: u32, km: u64) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("set_svc"),
+            (admin_id, nonce, rentals, km),
+        );
+
+        write_svc_rule(&env, SvcRule { rentals, km });
+        event::set_svc(&env, rentals, km);
+    }
+
+    // Set the reservation policy.
+    // ADMIN EXPECTED (Owner role)
+    // Necessary the nonce (int), reservation ttl in seconds (int), free cancellation period in seconds (int) and cancellation fee (int).
+    fn set_policy(env: Env, admin: Signature, nonce: i128, ttl: u64, free: u64, fee: i128) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::Owner);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("set_policy"),
+            (admin_id, nonce, ttl, free, fee),
+        );
+
+        // a reservation needs some time to be taken
+        if ttl == 0 {
+            panic_with_error!(&env, Error::InvalidDuration)
+        }
+        // the cancellation fee can't be negative
+        if fee < 0 {
+            panic_with_error!(&env, Error::InvalidAmount)
+        }
+
+        write_policy(&env, ResvPolicy { ttl, free, fee });
+        event::set_policy(&env, ttl, free, fee);
+    }
+
+    // Add an insurance product that the clients can buy at the reservation. Return the product id.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the nonce (int), the premium (int) paid to the claims pool and the deductible (int).
+    fn add_insur(env: Env, admin: Signature, nonce: i128, premium: i128, deduct: i128) -> u32 {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("add_insur"),
+            (admin_id, nonce, premium, deduct),
+        );
+
+        // the premium and the deductible can't be negative
+        if premium < 0 || deduct < 0 {
+            panic_with_error!(&env, Error::InvalidAmount)
+        }
+
+        let id = add_insurance(&env, Insurance { premium, deduct });
+        event::add_insur(&env, id, premium, deduct);
+        id
+    }
+
+    // Set the multiplier of the daily rate on saturdays and sundays.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the nonce (int) and the multiplier in basis points (int, 10000 = 1x).
+    fn set_wkend(env: Env, admin: Signature, nonce: i128, bps: u32) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(&env, &admin, symbol!("set_wkend"), (admin_id, nonce, bps));
+
+        // a rental can't be free
+        if bps == 0 {
+            panic_with_error!(&env, Error::InvalidAmount)
+        }
+
+        write_weekend(&env, bps);
+        event::set_wkend(&env, bps);
+    }
+
+    // Add a season: a multiplier of the daily rate for the days starting in the period [start, end).
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the nonce (int), start and end (int) timestamps of the period and the multiplier in basis points (int).
+    // A season can't overlap the other seasons.
+    fn add_season(env: Env, admin: Signature, nonce: i128, start: u64, end: u64, bps: u32) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("add_season"),
+            (admin_id, nonce, start, end, bps),
+        );
+
+        if end <= start {
+            panic_with_error!(&env, Error::InvalidDuration)
+        }
+        if bps == 0 {
+            panic_with_error!(&env, Error::InvalidAmount)
+        }
+
+        // keep the seasons sorted by the start of the period
+        let mut seasons = read_seasons(&env);
+        let mut index = seasons.len();
+        for (i, season) in seasons.iter().enumerate() {
+            let season = season.unwrap();
+            if start < season.end && season.start < end {
+                panic_with_error!(&env, Error::SeasonOverlap)
+            }
+            if start < season.start && index == seasons.len() {
+                index = i as u32;
+            }
+        }
+        insert_at(&mut seasons, index, Season { start, end, bps });
+        write_seasons(&env, seasons);
+        event::add_season(&env, start, end, bps);
+    }
+
+    // Remove the season that starts at start.
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the nonce (int) and the start (int) timestamp of the season.
+    fn rm_season(env: Env, admin: Signature, nonce: i128, start: u64) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(&env, &admin, symbol!("rm_season"), (admin_id, nonce, start));
+
+        let mut seasons = read_seasons(&env);
+        let mut index = None;
+        for (i, season) in seasons.iter().enumerate() {
+            if season.unwrap().start == start {
+                index = Some(i as u32);
+                break;
+            }
+        }
+        match index {
+            Some(i) => seasons.remove(i),
+            None => panic_with_error!(&env, Error::SeasonNotExists),
+        };
+        write_seasons(&env, seasons);
+        event::rm_season(&env, start);
+    }
+
+    // Set the surge multiplier applied when the share of reserved or rented cars reaches util (%).
+    // ADMIN EXPECTED (FleetManager role)
+    // Necessary the nonce (int), the share of cars (int, %) and the multiplier in basis points (int).
+    // A multiplier of 10000 (1x) removes the surge.
+    fn set_surge(env: Env, admin: Signature, nonce: i128, util: u32, bps: u32) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::FleetManager);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("set_surge"),
+            (admin_id, nonce, util, bps),
+        );
+
+        if util > 100 || bps == 0 {
+            panic_with_error!(&env, Error::InvalidAmount)
+        }
+
+        // keep the surges sorted by the share of cars, replacing the one with the same share
+        let mut surges = read_surges(&env);
+        let mut index = surges.len();
+        for (i, surge) in surges.iter().enumerate() {
+            if surge.unwrap().util >= util {
+                index = i as u32;
+                break;
+            }
+        }
+        if index < surges.len() && surges.get_unchecked(index).unwrap().util == util {
+            surges.remove(index);
+        }
+        if bps != BASIS_POINTS {
+            insert_at(&mut surges, index, Surge { util, bps });
+        }
+        write_surges(&env, surges);
+        event::set_surge(&env, util, bps);
+    }
+
+    // Approved a client request to be allowed to rent a car.
+    // ADMIN EXPECTED (ClientApprover role)
+    // Necessary the client public key
+    fn appr_req(env: Env, admin: Signature, client: Identifier, nonce: i128) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::ClientApprover);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("appr_req"),
+            (admin_id, nonce, &client),
+        );
+
+        // the client must have opened a request. A suspended client is reinstated (reinstate)
+        check_client_request(&env, &client);
+        approve_client(&env, &client);
+    }
+
+    // Deny a client request to be allowed to rent a car.
+    // ADMIN EXPECTED (ClientApprover role)
+    // Necessary the client public key
+    fn deny_req(env: Env, admin: Signature, client: Identifier, nonce: i128) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::ClientApprover);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("deny_req"),
+            (admin_id, nonce, &client),
+        );
+
+        // the client must have opened a request
+        check_client_request(&env, &client);
+        decline_client(&env, &client);
+    }
+
+    // Approve and decline several client requests with one signature.
+    // ADMIN EXPECTED (ClientApprover role)
+    // Necessary the nonce (int), the clients to approve and the clients to decline (lists of public keys).
+    fn batch_req(
+        env: Env,
+        admin: Signature,
+        nonce: i128,
+        approve: Vec<Identifier>,
+        deny: Vec<Identifier>,
+    ) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::ClientApprover);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("batch_req"),
+            (admin_id, nonce, &approve, &deny),
+        );
+
+        // every client must have opened a request, otherwise nothing is changed
+        for client in approve.iter() {
+            let client = client.unwrap();
+            check_client_request(&env, &client);
+            approve_client(&env, &client);
+        }
+        for client in deny.iter() {
+            let client = client.unwrap();
+            check_client_request(&env, &client);
+            decline_client(&env, &client);
+        }
+    }
+
+    // Set the validity period of the approvals. The approvals given before keep their expiration.
+    // ADMIN EXPECTED (Owner role)
+    // Necessary the nonce (int) and the validity period in seconds (int, 0 = the approvals don't expire).
+    fn set_valid(env: Env, admin: Signature, nonce: i128, ttl: u64) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::Owner);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(&env, &admin, symbol!("set_valid"), (admin_id, nonce, ttl));
+
+        write_approval_ttl(&env, ttl);
+        event::set_valid(&env, ttl);
+    }
+
+    // Suspend an approved client. A suspended client can't reserve a car, but can finish the current rentals.
+    // ADMIN EXPECTED (ClientApprover role)
+    // Necessary the client public key
+    fn suspend(env: Env, admin: Signature, client: Identifier, nonce: i128) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::ClientApprover);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(&env, &admin, symbol!("suspend"), (admin_id, nonce, &client));
+
+        // only an approved client can be suspended
+        match read_client(&env, client.clone()) {
+            ClientStatus::Unknown => panic_with_error!(&env, Error::ClientNotFound),
+            ClientStatus::Approved => {}
+            _ => panic_with_error!(&env, Error::InvalidClientStatus),
+        }
+        write_client(&env, client.clone(), ClientStatus::Suspended);
+        event::suspend(&env, client);
+    }
+
+    // Reinstate a suspended client. The client status goes back to Approved.
+    // ADMIN EXPECTED (ClientApprover role)
+    // Necessary the client public key
+    fn reinstate(env: Env, admin: Signature, client: Identifier, nonce: i128) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::ClientApprover);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("reinstate"),
+            (admin_id, nonce, &client),
+        );
+
+        // only a suspended client can be reinstated
+        match read_client(&env, client.clone()) {
+            ClientStatus::Unknown => panic_with_error!(&env, Error::ClientNotFound),
+            ClientStatus::Suspended => {}
+            _ => panic_with_error!(&env, Error::InvalidClientStatus),
+        }
+        write_client(&env, client.clone(), ClientStatus::Approved);
+        event::reinstate(&env, client);
+    }
+
+    // Revoke a client. A revoked client can't reserve a car or open a new request, but can finish the current rentals.
+    // ADMIN EXPECTED (ClientApprover role)
+    // Necessary the client public key
+    fn revoke(env: Env, admin: Signature, client: Identifier, nonce: i128) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::ClientApprover);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(&env, &admin, symbol!("revoke"), (admin_id, nonce, &client));
+
+        match read_client(&env, client.clone()) {
+            ClientStatus::Unknown => panic_with_error!(&env, Error::ClientNotFound),
+            ClientStatus::Revoked => panic_with_error!(&env, Error::InvalidClientStatus),
+            _ => {}
+        }
+        write_client(&env, client.clone(), ClientStatus::Revoked);
+        event::revoke(&env, client);
+    }
+
+    // Accept the client drop car
+    // ADMIN EXPECTED (ReturnInspector role)
+    // Necessary the car plate (str), nonce (int), the odometer (int) and the fuel level (int, %) confirmed by the inspector.
+    // The late fee, the mileage fee and the fuel surcharge are charged and the deposit left is refunded.
+    fn accpt_drop(env: Env, admin: Signature, nonce: i128, plate: Bytes, odometer: u64, fuel: u32) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::ReturnInspector);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("accpt_drop"),
+            (admin_id, nonce, &plate, odometer, fuel),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        //  check if the car was rented
+        if !has_rented_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarIsNotRented)
+        }
+
+        //  get the rended car data
+        let mut rented_car_data = read_rented_car(&env, &plate);
+
+        // check if the car status is in drop review. If not it is not requested to drop
+        if rented_car_data.status != RentedCarStatus::DropReview {
+            panic_with_error!(&env, Error::CarIsNotInDropReview)
+        }
+
+        // the confirmed readings replace the ones reported by the client
+        check_reading(&env, odometer, fuel, rented_car_data.odo_out);
+        rented_car_data.odo_in = odometer;
+        rented_car_data.fuel_in = fuel;
+        let mut car_data = read_car(&env, plate.clone());
+        rented_car_data.km_fee = km_fee(&car_data, &rented_car_data);
+        rented_car_data.fuel_fee = fuel_fee(&car_data, &rented_car_data);
+        car_data.mileage = odometer;
+        // the car stays at the drop-off branch
+        car_data.location = rented_car_data.dropoff.clone();
+        write_car(&env, &plate, car_data);
+
+        // count the rental in the usage since the last service. The car is flagged when it becomes due
+        let mut state = service_state(&env, &plate);
+        let was_due = state.due;
+        state.rentals += 1;
+        state.km += driven_km(&rented_car_data);
+        state.due = is_service_due(&env, &state);
+        if state.due && !was_due {
+            event::svc_due(&env, plate.clone(), state.rentals, state.km);
+        }
+        write_svc_state(&env, &plate, state);
+
+        // charge the late fee and the usage fees. It's taken from the deposit first
+        let charges = rented_car_data.late_fee + rented_car_data.km_fee + rented_car_data.fuel_fee;
+        charge_renter(&env, &mut rented_car_data, charges);
+
+        // refund the deposit left in escrow to the renter
+        transfer_from_contract_to_account(&env, &rented_car_data.renter, &rented_car_data.deposit);
+
+        // remove the rented car from the contract storage.
+        // The car is not rented anymore by any client.
+        remove_rented_car(&env, &plate);
+        close_rental(&env, &plate, &rented_car_data, Outcome::Completed);
+        event::accpt_drop(&env, plate, rented_car_data.renter, rented_car_data.deposit);
+    }
+
+    // Deny the client drop car with a damage report
+    // ADMIN EXPECTED (ReturnInspector role)
+    // Necessary the car plate (str), nonce (int), the reason code (int), estimated cost (int) and the hash of the off-chain evidence.
+    // The client accepts the report (accpt_dmg), and the cost is withheld from the deposit, or disputes it (dispute).
+    fn deny_drop(
+        env: Env,
+        admin: Signature,
+        nonce: i128,
+        plate: Bytes,
+        reason: u32,
+        cost: i128,
+        evidence: BytesN<32>,
+    ) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::ReturnInspector);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("deny_drop"),
+            (admin_id, nonce, &plate, reason, cost, &evidence),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        // check if car is rented
+        if !has_rented_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarIsNotRented)
+        }
+
+        // get the rented car data
+        let mut rented_car_data = read_rented_car(&env, &plate);
+
+        // verify if car status id DropReview. If not it is not requested to drop
+        if rented_car_data.status != RentedCarStatus::DropReview {
+            panic_with_error!(&env, Error::CarIsNotInDropReview)
+        }
+
+        // the estimated cost can't be greater than the deposit in escrow plus the part paid by the claims pool
+        if cost < 0 || cost - claim(&env, &rented_car_data, cost) > rented_car_data.deposit {
+            panic_with_error!(&env, Error::InvalidAmount)
+        }
+
+        // attach the damage report
+        rented_car_data.reason = reason;
+        rented_car_data.dmg_cost = cost;
+        rented_car_data.evidence = evidence.clone();
+        rented_car_data.denials.push_back(Denial {
+            reason,
+            cost,
+            evidence: evidence.clone(),
+            denied: env.ledger().timestamp(),
+        });
+
+        // change the rented car status to DropReviewDenied
+        rented_car_data.status = RentedCarStatus::DropReviewDenied;
+        // write the rented car data to the contract storage
+        write_rented_car(&env, &plate, rented_car_data.clone());
+        event::deny_drop(&env, plate, rented_car_data.renter, reason, cost, evidence);
+    }
+
+    // Settle a disputed damage report
+    // ADMIN EXPECTED (Arbiter role)
+    // Necessary the car plate (str), nonce (int) and the amount (int) to withhold, up to the estimated cost of the report.
+    // The car goes back to DropReview, so the drop can be accepted.
+    fn settle(env: Env, admin: Signature, nonce: i128, plate: Bytes, amount: i128) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::Arbiter);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("settle"),
+            (admin_id, nonce, &plate, amount),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        // check if car is rented
+        if !has_rented_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarIsNotRented)
+        }
+
+        let mut rented_car_data = read_rented_car(&env, &plate);
+        if rented_car_data.status != RentedCarStatus::InDispute {
+            panic_with_error!(&env, Error::CarIsNotInDispute)
+        }
+
+        // the arbiter can't withhold more than the estimated cost of the report
+        if amount < 0 || amount > rented_car_data.dmg_cost {
+            panic_with_error!(&env, Error::InvalidAmount)
+        }
+
+        withhold_damage(&env, &plate, &mut rented_car_data, amount);
+        write_rented_car(&env, &plate, rented_car_data.clone());
+        event::settle(&env, plate, rented_car_data.renter, amount);
+    }
+    // Score the return of a completed rental. The score builds the reputation of the client.
+    // ADMIN EXPECTED (ReturnInspector role)
+    // Necessary the nonce (int), the rental id (int, informed in the history) and the score (int, 1 to 5).
+    fn score_ret(env: Env, admin: Signature, nonce: i128, id: u32, score: u32) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::ReturnInspector);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("score_ret"),
+            (admin_id, nonce, id, score),
+        );
+
+        check_score(&env, score);
+        let mut rental = read_rental(&env, id);
+        if rental.outcome != Outcome::Completed {
+            panic_with_error!(&env, Error::RentalNotCompleted)
+        }
+        if rental.score != 0 {
+            panic_with_error!(&env, Error::AlreadyRated)
+        }
+
+        rental.score = score;
+        write_rental(&env, &rental);
+        let mut reputation = read_reputation(&env, &rental.renter);
+        reputation.scores += 1;
+        reputation.total += score;
+        write_reputation(&env, &rental.renter, reputation);
+        event::score_ret(&env, rental.renter, id, score);
+    }
+
+    // Change the admin for this contract
+    // ADMIN EXPECTED (Owner role)
+    // Necessary the new_admin public key, nonce (int), new admin signature (str).
+    fn set_admin(env: Env, admin: Signature, nonce: i128, new_admin: Identifier) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::Owner);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("set_admin"),
+            (&admin_id, nonce, &new_admin),
+        );
+        // write the new_admin to the contract storage
+        write_admin(&env, new_admin.clone());
+        event::set_admin(&env, admin_id, new_admin);
+    }
+
+    // Set the tier of a client. The tier limits the number of cars reserved, rented or booked by the client at the same time.
+    // ADMIN EXPECTED (ClientApprover role)
+    // Necessary the client public key, the tier (int) and nonce (int).
+    fn set_tier(env: Env, admin: Signature, nonce: i128, client: Identifier, tier: u32) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::ClientApprover);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("set_tier"),
+            (admin_id, nonce, &client, tier),
+        );
+
+        // the client must have opened a request
+        if read_client(&env, client.clone()) == ClientStatus::Unknown {
+            panic_with_error!(&env, Error::ClientNotFound)
+        }
+
+        write_tier(&env, &client, tier);
+        event::set_tier(&env, client, tier);
+    }
+
+    // Set the max number of cars reserved, rented or booked at the same time by the clients of a tier.
+    // ADMIN EXPECTED (Owner role)
+    // Necessary the tier (int), the limit (int) and nonce (int).
+    fn set_limit(env: Env, admin: Signature, nonce: i128, tier: u32, limit: u32) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::Owner);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("set_limit"),
+            (admin_id, nonce, tier, limit),
+        );
+
+        write_tier_limit(&env, tier, limit);
+        event::set_limit(&env, tier, limit);
+    }
+
+    // Set the auto approval: open_req approves the clients with at least count scored returns
+    // and an average return score * 100 of at least score.
+    // ADMIN EXPECTED (ClientApprover role)
+    // Necessary the nonce (int), the min average score * 100 (int, e.g. 450 for 4.5) and the min number of scored returns (int, 0 disables).
+    fn set_auto(env: Env, admin: Signature, nonce: i128, score: u32, count: u32) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::ClientApprover);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("set_auto"),
+            (admin_id, nonce, score, count),
+        );
+
+        // the average score is between 1 and 5
+        if score > 500 {
+            panic_with_error!(&env, Error::InvalidScore)
+        }
+
+        write_auto_approval(&env, AutoAppr { score, count });
+        event::set_auto(&env, score, count);
+    }
+
+    // Set the loyalty discount of the clients with at least rentals completed rentals.
+    // ADMIN EXPECTED (Owner role)
+    // Necessary the nonce (int), the number of completed rentals (int) and the discount in basis points (int, 0 removes it).
+    fn set_loyal(env: Env, admin: Signature, nonce: i128, rentals: u32, bps: u32) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::Owner);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("set_loyal"),
+            (admin_id, nonce, rentals, bps),
+        );
+
+        // the discount can't be greater than the rental
+        if bps > BASIS_POINTS {
+            panic_with_error!(&env, Error::InvalidAmount)
+        }
+
+        // keep the discounts sorted by the number of rentals, replacing the one with the same number
+        let mut loyalty = read_loyalty(&env);
+        let mut index = loyalty.len();
+        for (i, discount) in loyalty.iter().enumerate() {
+            if discount.unwrap().rentals >= rentals {
+                index = i as u32;
+                break;
+            }
+        }
+        if index < loyalty.len() && loyalty.get_unchecked(index).unwrap().rentals == rentals {
+            loyalty.remove(index);
+        }
+        if bps != 0 {
+            insert_at(&mut loyalty, index, Loyalty { rentals, bps });
+        }
+        write_loyalty(&env, loyalty);
+        event::set_loyal(&env, rentals, bps);
+    }
+
+    // Grant a role to an operator
+    // ADMIN EXPECTED (Owner role)
+    // Necessary the operator public key, the role (FleetManager, ReturnInspector, ClientApprover or Arbiter) and nonce (int).
+    fn grant_role(env: Env, admin: Signature, nonce: i128, operator: Identifier, role: Role) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::Owner);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("grant_role"),
+            (admin_id, nonce, &operator, role),
+        );
+
+        // the owner is changed only with set_admin
+        if role == Role::Owner {
+            panic_with_error!(&env, Error::InvalidRole)
+        }
+        // the arbiter can't settle the disputes of its own inspections
+        let conflict = match role {
+            Role::Arbiter => Some(Role::ReturnInspector),
+            Role::ReturnInspector => Some(Role::Arbiter),
+            _ => None,
+        };
+        if let Some(conflict) = conflict {
+            if has_role(&env, &operator, conflict) {
+                panic_with_error!(&env, Error::RoleConflict)
+            }
+        }
+
+        write_roles(&env, &operator, read_roles(&env, &operator) | role as u32);
+        event::grant_role(&env, operator, role);
+    }
+
+    // Revoke a role from an operator
+    // ADMIN EXPECTED (Owner role)
+    // Necessary the operator public key, the role (FleetManager, ReturnInspector, ClientApprover or Arbiter) and nonce (int).
+    fn revk_role(env: Env, admin: Signature, nonce: i128, operator: Identifier, role: Role) {
+        // verify if admin informed has the role to execute this call.
+        check_role(&env, &admin, Role::Owner);
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &admin, &nonce);
+        // get the admin identifier based on the admin signature.
+        let admin_id = admin.identifier(&env);
+        // verify the signature of the admin to that specific call.
+        verify(
+            &env,
+            &admin,
+            symbol!("revk_role"),
+            (admin_id, nonce, &operator, role),
+        );
+
+        // the owner is changed only with set_admin
+        if role == Role::Owner {
+            panic_with_error!(&env, Error::InvalidRole)
+        }
+
+        write_roles(
+            &env,
+            &operator,
+            read_roles(&env, &operator) & !(role as u32),
+        );
+        event::revk_role(&env, operator, role);
+    }
+
+    // Client open request to be able to reserve and rent a caker
+    // Necessary the client public key, nonce (int) and the hash of the off-chain KYC documents (zeros if not informed).
+    // The request waits in the pending queue until it is approved or declined.
+    fn open_req(env: Env, client: Signature, nonce: i128, kyc: BytesN<32>) {
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &client, &nonce);
+        // get the client identifier based on the client signature.
+        let client_identifier = client.identifier(&env);
+        verify(
+            &env,
+            &client,
+            symbol!("open_req"),
+            (&client_identifier, nonce, &kyc),
+        );
+        // an approved, suspended or revoked client can't go back to Pending. An expired approval opens a new request
+        match read_client(&env, client_identifier.clone()) {
+            ClientStatus::Unknown
+            | ClientStatus::Pending
+            | ClientStatus::Declined
+            | ClientStatus::Expired => {}
+            _ => panic_with_error!(&env, Error::InvalidClientStatus),
+        }
+        // write the client status Pending to this specific client in the storage
+        write_client(&env, client_identifier.clone(), ClientStatus::Pending);
+        add_pending(
+            &env,
+            PendReq {
+                client: client_identifier.clone(),
+                submitted: env.ledger().timestamp(),
+                kyc,
+            },
+        );
+        event::open_req(&env, client_identifier.clone());
+
+        // a client with a good reputation is approved without waiting for an approver
+        if has_good_reputation(&env, &client_identifier) {
+            approve_client(&env, &client_identifier);
+        }
+    }
+
+    // Client reserve a car to be able to take it later
+    // Necessary the client public key, nonce (int), car plate (str), the number of days (int) of the rental,
+    // the insurance product (int, 0 without insurance) and the drop-off branch (str, empty to return the car where it is).
+    // The car is taken at its current branch. A one-way rental pays the relocation fee of the drop-off branch at the pickup.
+    // The car deposit is transferred from the client to the contract (escrow), and the insurance premium to the claims pool.
+    fn resrve_car(
+        env: Env,
+        client: Signature,
+        nonce: i128,
+        plate: Bytes,
+        days: u32,
+        insurance: u32,
+        dropoff: Bytes,
+    ) {
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &client, &nonce);
+        // get the client identifier based on the client signature.
+        let client_identifier = client.identifier(&env);
+        // verify the signature of the client to that specific call.
+        verify(
+            &env,
+            &client,
+            symbol!("resrve_car"),
+            (&client_identifier, nonce, &plate, days, insurance, &dropoff),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        // check if the client is authorized to reserve a car (client status approved)
+        check_client_authorized(&env, &client_identifier);
+        // check the limit of cars reserved, rented or booked at the same time by the client tier
+        check_rental_limit(
+            &env,
+            &client_identifier,
+            held_cars(&env, &client_identifier),
+        );
+        // check if the car exists in the contract storage
+        if !has_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarNotExists)
+        }
+        // a car in maintenance can't be reserved
+        let car_data = read_car(&env, plate.clone());
+        if car_data.maint {
+            panic_with_error!(&env, Error::CarInMaintenance)
+        }
+
+        // check if the car is already rented. An expired reservation is released and the car can be reserved again
+        if has_rented_car(&env, &plate) {
+            if !is_reservation_expired(&env, &read_rented_car(&env, &plate)) {
+                panic_with_error!(&env, Error::CarAlreadyRented)
+            }
+            let released = release_reservation(&env, &plate, Outcome::Expired);
+            event::rls_expird(&env, plate.clone(), released.renter, released.deposit);
+        }
+
+        // the rental needs at least one day
+        if days == 0 {
+            panic_with_error!(&env, Error::InvalidDuration)
+        }
+
+        // the reservation can't overlap a booking, until the latest return (taking the car just before expiring)
+        let now = env.ledger().timestamp();
+        let expires = now + read_policy(&env).ttl;
+        if overlaps_booking(&env, &plate, now, expires + (days as u64) * DAY_IN_SECONDS) {
+            panic_with_error!(&env, Error::BookingOverlap)
+        }
+        // nor a maintenance window
+        if overlaps_maint(&env, &plate, now, expires + (days as u64) * DAY_IN_SECONDS) {
+            panic_with_error!(&env, Error::MaintOverlap)
+        }
+
+        // the insurance product must exist
+        let coverage = insurance_coverage(&env, insurance);
+
+        // the car is taken at its current branch and returned at the drop-off branch, that must exist
+        let pickup = car_data.location.clone();
+        let dropoff = if dropoff.is_empty() {
+            pickup.clone()
+        } else {
+            dropoff
+        };
+        let reloc = relocation_fee(&env, &pickup, &dropoff);
+
+        // the rental price is quoted now, with the pricing rules in force and the loyalty discount of the client
+        let quote = price_quote(&env, &car_data, now, days);
+        let price = quote.total - loyalty_discount(&env, &client_identifier, quote.total);
+
+        // take the car deposit into escrow and the insurance premium to the claims pool
+        transfer_from_account_to_contract(&env, &client_identifier, &car_data.deposit);
+        if coverage.premium > 0 {
+            transfer_from_account_to_contract(&env, &client_identifier, &coverage.premium);
+            write_pool(&env, read_pool(&env) + coverage.premium);
+        }
+
+        // write the rented car data to the contract storage with status Reserved
+        write_rented_car(
+            &env,
+            &plate,
+            RentedCarDataKey {
+                renter: client_identifier.clone(),
+                status: RentedCarStatus::Reserved,
+                days,
+                deposit: car_data.deposit,
+                reason: 0,
+                reserved: now,
+                expires,
+                picked_up: 0,
+                due: 0,
+                returned: 0,
+                late_fee: 0,
+                denials: Vec::new(&env),
+                withheld: 0,
+                dmg_cost: 0,
+                evidence: BytesN::from_array(&env, &[0; 32]),
+                price,
+                odo_out: 0,
+                fuel_out: 0,
+                odo_in: 0,
+                fuel_in: 0,
+                km_fee: 0,
+                fuel_fee: 0,
+                insurance,
+                premium: coverage.premium,
+                deduct: coverage.deduct,
+                covered: 0,
+                pickup,
+                dropoff,
+                reloc,
+            },
+        );
+        event::resrve_car(&env, plate, client_identifier, days);
+    }
+
+    // Client cancel a reservation
+    // Necessary the client public key, nonce (int), car plate (str).
+    // The deposit is refunded. After the free cancellation period, the cancellation fee is withheld.
+    fn cancl_resv(env: Env, client: Signature, nonce: i128, plate: Bytes) {
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &client, &nonce);
+        // get the client identifier based on the client signature.
+        let client_identifier = client.identifier(&env);
+        // verify the signature of the client to that specific call.
+        verify(
+            &env,
+            &client,
+            symbol!("cancl_resv"),
+            (&client_identifier, nonce, &plate),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        // check if car is reserved
+        if !has_rented_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarIsNotRented)
+        }
+
+        let mut rented_car_data = read_rented_car(&env, &plate);
+        //  check if the renter is the same client that are requesting to cancel the reservation
+        if rented_car_data.renter != client_identifier {
+            panic_with_error!(&env, Error::ClientIsNotRenter)
+        }
+        // only a reservation can be cancelled. After taking the car it must be dropped
+        if rented_car_data.status != RentedCarStatus::Reserved {
+            panic_with_error!(&env, Error::CarIsNotReserved)
+        }
+
+        // free cancellation just after the reservation
+        let policy = read_policy(&env);
+        let refund = if env.ledger().timestamp() < rented_car_data.reserved + policy.free {
+            refund_premium(&env, &mut rented_car_data);
+            transfer_from_contract_to_account(&env, &client_identifier, &rented_car_data.deposit);
+            remove_rented_car(&env, &plate);
+            close_rental(&env, &plate, &rented_car_data, Outcome::Cancelled);
+            rented_car_data.deposit
+        } else {
+            release_reservation(&env, &plate, Outcome::Cancelled).deposit
+        };
+        event::cancl_resv(&env, plate, client_identifier, refund);
+    }
+
+    // Client book a car for a future period
+    // Necessary the client public key, nonce (int), car plate (str), start and end (int) timestamps of the period [start, end),
+    // the insurance product (int, 0 without insurance), the pickup branch (str, empty for the current branch of the car)
+    // and the drop-off branch (str, empty for the pickup branch).
+    // The period can't overlap other bookings or the current rental of the car. The car deposit is transferred to the contract (escrow),
+    // and the insurance premium to the claims pool.
+    fn book_car(
+        env: Env,
+        client: Signature,
+        nonce: i128,
+        plate: Bytes,
+        start: u64,
+        end: u64,
+        insurance: u32,
+        pickup: Bytes,
+        dropoff: Bytes,
+    ) {
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &client, &nonce);
+        // get the client identifier based on the client signature.
+        let client_identifier = client.identifier(&env);
+        // verify the signature of the client to that specific call.
+        verify(
+            &env,
+            &client,
+            symbol!("book_car"),
+            (
+                &client_identifier,
+                nonce,
+                &plate,
+                start,
+                end,
+                insurance,
+                &pickup,
+                &dropoff,
+            ),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        // check if the client is authorized to book a car (client status approved)
+        check_client_authorized(&env, &client_identifier);
+        // check the limit of cars reserved, rented or booked at the same time by the client tier
+        check_rental_limit(
+            &env,
+            &client_identifier,
+            held_cars(&env, &client_identifier),
+        );
+        // a car in maintenance can't be booked
+        let car_data = read_car(&env, plate.clone());
+        if car_data.maint {
+            panic_with_error!(&env, Error::CarInMaintenance)
+        }
+
+        // the period must be in the future
+        let now = env.ledger().timestamp();
+        if start < now || end <= start {
+            panic_with_error!(&env, Error::InvalidDuration)
+        }
+        if !is_period_free(&env, &plate, start, end) {
+            panic_with_error!(&env, Error::BookingOverlap)
+        }
+        if overlaps_maint(&env, &plate, start, end) {
+            panic_with_error!(&env, Error::MaintOverlap)
+        }
+        // the insurance product must exist
+        let coverage = insurance_coverage(&env, insurance);
+        // the pickup and drop-off branches must exist
+        let pickup = if pickup.is_empty() {
+            car_data.location.clone()
+        } else {
+            pickup
+        };
+        let dropoff = if dropoff.is_empty() {
+            pickup.clone()
+        } else {
+            dropoff
+        };
+        if !has_branch(&env, &pickup) {
+            panic_with_error!(&env, Error::BranchNotExists)
+        }
+        let reloc = relocation_fee(&env, &pickup, &dropoff);
+        // the rental price is quoted now, with the pricing rules in force and the loyalty discount of the client
+        let quote = price_quote(&env, &car_data, start, started_days(start, end));
+        let price = quote.total - loyalty_discount(&env, &client_identifier, quote.total);
+
+        // take the car deposit into escrow and the insurance premium to the claims pool
+        transfer_from_account_to_contract(&env, &client_identifier, &car_data.deposit);
+        if coverage.premium > 0 {
+            transfer_from_account_to_contract(&env, &client_identifier, &coverage.premium);
+            write_pool(&env, read_pool(&env) + coverage.premium);
+        }
+
+        // keep the bookings sorted by the start of the period
+        let mut bookings = read_bookings(&env, &plate);
+        let mut index = bookings.len();
+        for (i, booking) in bookings.iter().enumerate() {
+            if booking.unwrap().start > start {
+                index = i as u32;
+                break;
+            }
+        }
+        insert_at(
+            &mut bookings,
+            index,
+            Booking {
+                renter: client_identifier.clone(),
+                start,
+                end,
+                deposit: car_data.deposit,
+                booked: now,
+                price,
+                pickup,
+                dropoff,
+                reloc,
+                insurance,
+                premium: coverage.premium,
+                deduct: coverage.deduct,
+            },
+        );
+        write_bookings(&env, &plate, bookings);
+        event::book_car(&env, plate, client_identifier, start, end);
+    }
+
+    // Client cancel a booking
+    // Necessary the client public key, nonce (int), car plate (str) and the start (int) of the booked period.
+    // The deposit is refunded. After the free cancellation period, the cancellation fee is withheld.
+    fn cancl_book(env: Env, client: Signature, nonce: i128, plate: Bytes, start: u64) {
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &client, &nonce);
+        // get the client identifier based on the client signature.
+        let client_identifier = client.identifier(&env);
+        // verify the signature of the client to that specific call.
+        verify(
+            &env,
+            &client,
+            symbol!("cancl_book"),
+            (&client_identifier, nonce, &plate, start),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        let mut bookings = read_bookings(&env, &plate);
+        let mut index = None;
+        for (i, booking) in bookings.iter().enumerate() {
+            let booking = booking.unwrap();
+            if booking.start == start && booking.renter == client_identifier {
+                index = Some(i as u32);
+                break;
+            }
+        }
+        let index = match index {
+            Some(index) => index,
+            None => panic_with_error!(&env, Error::BookingNotExists),
+        };
+        let booking = bookings.get_unchecked(index).unwrap();
+        bookings.remove(index);
+        write_bookings(&env, &plate, bookings);
+
+        // free cancellation just after the booking
+        let free = env.ledger().timestamp() < booking.booked + read_policy(&env).free;
+        let refund = release_booking(&env, &plate, &booking, Outcome::Cancelled, !free);
+        event::cancl_book(&env, plate, client_identifier, refund);
+    }
+
+    // Release an expired reservation and the bookings not taken until the end of their period. Anyone can call it.
+    // Necessary the car plate (str).
+    // The cancellation fee is withheld and the rest of the deposit is refunded to the client.
+    fn rls_expird(env: Env, plate: Bytes) {
+        let plate = canonical_plate(&env, &plate);
+        // release the bookings that ended without taking the car
+        let now = env.ledger().timestamp();
+        let mut released = 0;
+        let mut bookings = Vec::new(&env);
+        for booking in read_bookings(&env, &plate).iter() {
+            let booking = booking.unwrap();
+            if booking.end <= now {
+                // no fee when the car was held by another rental in the booked period
+                let held = held_in_period(&env, &plate, booking.start, booking.end);
+                let refund = release_booking(&env, &plate, &booking, Outcome::Expired, !held);
+                event::rls_expird(&env, plate.clone(), booking.renter, refund);
+                released += 1;
+            } else {
+                bookings.push_back(booking);
+            }
+        }
+        write_bookings(&env, &plate, bookings);
+
+        if has_rented_car(&env, &plate)
+            && is_reservation_expired(&env, &read_rented_car(&env, &plate))
+        {
+            let reservation = release_reservation(&env, &plate, Outcome::Expired);
+            event::rls_expird(&env, plate, reservation.renter, reservation.deposit);
+        } else if released == 0 {
+            // nothing to release
+            if !has_rented_car(&env, &plate) {
+                panic_with_error!(&env, Error::CarIsNotRented)
+            }
+            if read_rented_car(&env, &plate).status != RentedCarStatus::Reserved {
+                panic_with_error!(&env, Error::CarIsNotReserved)
+            }
+            panic_with_error!(&env, Error::ReservationNotExpired)
+        }
+    }
+
+    // Client accept the damage report of a denied drop
+    // Necessary the client public key, nonce (int), car plate (str).
+    // The estimated cost is withheld from the deposit and the car goes back to DropReview, so the drop can be accepted.
+    fn accpt_dmg(env: Env, client: Signature, nonce: i128, plate: Bytes) {
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &client, &nonce);
+        // get the client identifier based on the client signature.
+        let client_identifier = client.identifier(&env);
+        // verify the signature of the client to that specific call.
+        verify(
+            &env,
+            &client,
+            symbol!("accpt_dmg"),
+            (&client_identifier, nonce, &plate),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        let mut rented_car_data = read_denied_drop(&env, &plate, &client_identifier);
+        let cost = rented_car_data.dmg_cost;
+        withhold_damage(&env, &plate, &mut rented_car_data, cost);
+        write_rented_car(&env, &plate, rented_car_data);
+        event::accpt_dmg(&env, plate, client_identifier, cost);
+    }
+
+    // Client dispute the damage report of a denied drop
+    // Necessary the client public key, nonce (int), car plate (str).
+    // The car status changes to InDispute until an arbiter settles it (settle).
+    fn dispute(env: Env, client: Signature, nonce: i128, plate: Bytes) {
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &client, &nonce);
+        // get the client identifier based on the client signature.
+        let client_identifier = client.identifier(&env);
+        // verify the signature of the client to that specific call.
+        verify(
+            &env,
+            &client,
+            symbol!("dispute"),
+            (&client_identifier, nonce, &plate),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        let mut rented_car_data = read_denied_drop(&env, &plate, &client_identifier);
+        rented_car_data.status = RentedCarStatus::InDispute;
+        write_rented_car(&env, &plate, rented_car_data.clone());
+        event::dispute(&env, plate, client_identifier, rented_car_data.dmg_cost);
+    }
+
+    // Client pay the charges owed from the previous rentals
+    // Necessary the client public key and nonce (int). The whole balance owed is transferred to the admin.
+    fn pay_owed(env: Env, client: Signature, nonce: i128) {
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &client, &nonce);
+        // get the client identifier based on the client signature.
+        let client_identifier = client.identifier(&env);
+        // verify the signature of the client to that specific call.
+        verify(
+            &env,
+            &client,
+            symbol!("pay_owed"),
+            (&client_identifier, nonce),
+        );
+
+        let owed = read_owed(&env, &client_identifier);
+        if owed == 0 {
+            panic_with_error!(&env, Error::NothingOwed)
+        }
+        transfer_from_account_to_account(&env, &client_identifier, &read_admin(&env), &owed);
+        write_owed(&env, &client_identifier, 0);
+        event::pay_owed(&env, client_identifier, owed);
+    }
+
+    // Client rate the car and the service of a completed rental
+    // Necessary the client public key, nonce (int), the rental id (int, informed in the history),
+    // the car rating (int, 1 to 5) and the service rating (int, 1 to 5).
+    fn rate_rent(env: Env, client: Signature, nonce: i128, id: u32, car: u32, service: u32) {
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &client, &nonce);
+        // get the client identifier based on the client signature.
+        let client_identifier = client.identifier(&env);
+        // verify the signature of the client to that specific call.
+        verify(
+            &env,
+            &client,
+            symbol!("rate_rent"),
+            (&client_identifier, nonce, id, car, service),
+        );
+
+        check_score(&env, car);
+        check_score(&env, service);
+        let mut rental = read_rental(&env, id);
+        // only the renter rates the rental
+        if rental.renter != client_identifier {
+            panic_with_error!(&env, Error::ClientIsNotRenter)
+        }
+        if rental.outcome != Outcome::Completed {
+            panic_with_error!(&env, Error::RentalNotCompleted)
+        }
+        if rental.car_rate != 0 {
+            panic_with_error!(&env, Error::AlreadyRated)
+        }
+
+        rental.car_rate = car;
+        rental.svc_rate = service;
+        write_rental(&env, &rental);
+        let mut rating = read_car_rating(&env, &rental.plate);
+        rating.count += 1;
+        rating.car += car;
+        rating.service += service;
+        write_car_rating(&env, &rental.plate, rating);
+        event::rate_rent(&env, rental.plate, client_identifier, car, service);
+    }
+
+    // Client take a car (previous reserved)
+    // Necessary the client public key, nonce (int), car plate (str), the odometer (int) and the fuel level (int, %) at the pickup.
+    fn take_car(env: Env, client: Signature, nonce: i128, plate: Bytes, odometer: u64, fuel: u32) {
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &client, &nonce);
+        // get the client identifier based on the client signature.
+        let client_identifier = client.identifier(&env);
+        // verify the signature of the client to that specific call.
+        verify(
+            &env,
+            &client,
+            symbol!("take_car"),
+            (&client_identifier, nonce, &plate, odometer, fuel),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        let now = env.ledger().timestamp();
+        // a booking of the client is taken when the car is free (no reservation or an expired one)
+        let free = !has_rented_car(&env, &plate)
+            || is_reservation_expired(&env, &read_rented_car(&env, &plate));
+        let (mut rented_car_data, due) = if free && has_booking(&env, &plate, &client_identifier) {
+            // only inside the booked period. The car must be returned until its end
+            let booking = take_booking(&env, &plate, &client_identifier, now);
+            // the booking becomes a rental, it is not one more car of the client
+            check_rental_limit(
+                &env,
+                &client_identifier,
+                held_cars(&env, &client_identifier),
+            );
+            if has_rented_car(&env, &plate) {
+                let released = release_reservation(&env, &plate, Outcome::Expired);
+                event::rls_expird(&env, plate.clone(), released.renter, released.deposit);
+            }
+            (booking_rental(&env, &booking), booking.end)
+        } else {
+            // check if car is reserved
+            if !has_rented_car(&env, &plate) {
+                panic_with_error!(&env, Error::CarIsNotRented)
+            }
+
+            // get car data from the contract storage
+            let rented_car_data = read_rented_car(&env, &plate);
+            //  check if the renter is the same client that are requesting to take the car
+            if rented_car_data.renter != client_identifier {
+                panic_with_error!(&env, Error::ClientIsNotRenter)
+            }
+            // check if the car status is Reserved. If not it is not possible to take the car
+            if rented_car_data.status != RentedCarStatus::Reserved {
+                panic_with_error!(&env, Error::CarIsNotReserved)
+            }
+            // an expired reservation can't be taken
+            if is_reservation_expired(&env, &rented_car_data) {
+                panic_with_error!(&env, Error::ReservationExpired)
+            }
+            // the car must be returned in the rental days from the pickup
+            let due = now + (rented_car_data.days as u64) * DAY_IN_SECONDS;
+            (rented_car_data, due)
+        };
+
+        // the odometer can't be lower than the car mileage
+        let car_data = read_car(&env, plate.clone());
+        check_reading(&env, odometer, fuel, car_data.mileage);
+        // the car is taken at the pickup branch
+        if car_data.location != rented_car_data.pickup {
+            panic_with_error!(&env, Error::CarNotAtBranch)
+        }
+
+        // charge the rental quoted at the reservation and the relocation fee from the client to the admin
+        let rental = rented_car_data.price + rented_car_data.reloc;
+        transfer_from_account_to_account(&env, &client_identifier, &read_admin(&env), &rental);
+
+        rented_car_data.picked_up = now;
+        rented_car_data.due = due;
+        rented_car_data.odo_out = odometer;
+        rented_car_data.fuel_out = fuel;
+
+        // change car status to rented
+        rented_car_data.status = RentedCarStatus::Rented;
+        // write the rented car data to the contract storage
+        let due = rented_car_data.due;
+        write_rented_car(&env, &plate, rented_car_data);
+        event::take_car(&env, plate, client_identifier, due);
+    }
+
+    // Client request to drop the car
+    // Necessary the client public key, nonce (int), car plate (str), the odometer (int) and the fuel level (int, %) at the return.
+    // The readings are confirmed by the inspector at the drop acceptance.
+    fn drop_car(env: Env, client: Signature, nonce: i128, plate: Bytes, odometer: u64, fuel: u32) {
+        // consume the nonce to avoid replay call contract.
+        verify_and_consume_nonce(&env, &client, &nonce);
+        // get the client identifier based on the client signature.
+        let client_identifier = client.identifier(&env);
+        // verify the signature of the client to that specific call.
+        verify(
+            &env,
+            &client,
+            symbol!("drop_car"),
+            (&client_identifier, nonce, &plate, odometer, fuel),
+        );
+
+        // canonicalize the plate informed (upper case, without separators).
+        let plate = canonical_plate(&env, &plate);
+
+        // check if car is rented
+        if !has_rented_car(&env, &plate) {
+            panic_with_error!(&env, Error::CarIsNotRented)
+        }
+
+        // get car data from the contract storage
+        let mut rented_car_data = read_rented_car(&env, &plate);
+        // check if the renter is the same client that are requesting to drop the car
+        if rented_car_data.renter != client_identifier {
+            panic_with_error!(&env, Error::ClientIsNotRenter)
+        }
+        // check if the car status is Rented. If not it is not possible to drop the car
+        // if the drop is denied, the client accepts (accpt_dmg) or disputes (dispute) the damage report
+        match rented_car_data.status {
+            RentedCarStatus::Rented => {
+                // the late fee is computed at the drop (the car return)
+                let now = env.ledger().timestamp();
+                let car_data = read_car(&env, plate.clone());
+                rented_car_data.returned = now;
+                rented_car_data.late_fee = late_fee(&car_data, &rented_car_data, now);
+                check_reading(&env, odometer, fuel, rented_car_data.odo_out);
+                rented_car_data.odo_in = odometer;
+                rented_car_data.fuel_in = fuel;
+                // change car status to DropReview
+                rented_car_data.status = RentedCarStatus::DropReview;
+                // write the rented car data to the contract storage
+                let late_fee = rented_car_data.late_fee;
+                write_rented_car(&env, &plate, rented_car_data);
+                event::drop_car(&env, plate, client_identifier, late_fee);
+            }
+            _ => panic_with_error!(&env, Error::CarIsNotWithRentedStatus),
+        }
+    }
+
+    // return the client status. Unknown if the client never opened a request
+    fn read_clnt(env: Env, client: Identifier) -> ClientStatus {
+        read_client(&env, client)
+    }
+
+    // return the nonce from this contract execution
+    fn nonce(env: Env, identifier: Identifier) -> i128 {
+        read_nonce(&env, &identifier)
+    }
+
+    // return the car data. Fails with CarNotExists for an unknown plate
     fn read_car(env: Env, plate: Bytes) -> CarDataKey {
+        let plate = canonical_plate(&env, &plate);
         read_car(&env, plate)
     }
 
-    // return the car renter and the status
-    fn read_rent(env: Env, plate: Bytes) -> RentedCarDataKey {
-        read_rented_car(&env, &plate)
+    // return the car renter and the status. Fails with CarIsNotRented if the car is not reserved or rented
+    fn read_rent(env: Env, plate: Bytes) -> RentedCarDataKey {
+        let plate = canonical_plate(&env, &plate);
+        read_rented_car(&env, &plate)
+    }
+
+    // verify if car is rented
+    // return true if car is rented
+    fn has_rent(env: Env, plate: Bytes) -> bool {
+        let plate = canonical_plate(&env, &plate);
+        has_rented_car(&env, &plate)
+    }
+
+    // return the outstanding charges (late fee, mileage fee and fuel surcharge) of the rented car
+    // while the car is with the client, the late fee is computed up to now.
+    // After the drop, the usage fees are computed with the readings reported by the client
+    fn charges(env: Env, plate: Bytes) -> i128 {
+        let plate = canonical_plate(&env, &plate);
+        if !has_rented_car(&env, &plate) {
+            return 0;
+        }
+
+        let rented_car_data = read_rented_car(&env, &plate);
+        match rented_car_data.status {
+            RentedCarStatus::Reserved => 0,
+            RentedCarStatus::Rented => late_fee(
+                &read_car(&env, plate),
+                &rented_car_data,
+                env.ledger().timestamp(),
+            ),
+            _ => {
+                let car_data = read_car(&env, plate);
+                rented_car_data.late_fee
+                    + km_fee(&car_data, &rented_car_data)
+                    + fuel_fee(&car_data, &rented_car_data)
+            }
+        }
+    }
+
+    // return the bookings of the car, sorted by the start of the period
+    fn bookings(env: Env, plate: Bytes) -> Vec<Booking> {
+        let plate = canonical_plate(&env, &plate);
+        read_bookings(&env, &plate)
+    }
+
+    // return true if the car can be booked for the period [from, to): the car is not in maintenance
+    // and the period doesn't overlap the bookings, the current rental or the maintenance windows
+    fn available(env: Env, plate: Bytes, from: u64, to: u64) -> bool {
+        let plate = canonical_plate(&env, &plate);
+        if to <= from {
+            panic_with_error!(&env, Error::InvalidDuration)
+        }
+        !read_car(&env, plate.clone()).maint
+            && is_period_free(&env, &plate, from, to)
+            && !overlaps_maint(&env, &plate, from, to)
+    }
+
+    // return the cars of the contract with their rental status, in the order they were added
+    // paginated: skip offset cars and return at most limit cars
+    fn list_cars(env: Env, offset: u32, limit: u32) -> Vec<CarInfo> {
+        let plates = read_plates(&env);
+        let mut cars = Vec::new(&env);
+        for plate in plates.iter().skip(offset as usize).take(limit as usize) {
+            cars.push_back(car_info(&env, plate.unwrap()));
+        }
+        cars
+    }
+
+    // return the cars available to be reserved (an expired reservation is available) at the branch, empty for all branches
+    // paginated: skip offset available cars and return at most limit cars
+    fn list_avail(env: Env, branch: Bytes, offset: u32, limit: u32) -> Vec<CarInfo> {
+        let plates = read_plates(&env);
+        let mut cars = Vec::new(&env);
+        let mut skipped = 0;
+        for plate in plates.iter() {
+            if cars.len() >= limit {
+                break;
+            }
+            let car = car_info(&env, plate.unwrap());
+            if car.status != CarStatus::Available {
+                continue;
+            }
+            if !branch.is_empty() && car.car.location != branch {
+                continue;
+            }
+            if skipped < offset {
+                skipped += 1;
+                continue;
+            }
+            cars.push_back(car);
+        }
+        cars
+    }
+
+    // return the cars reserved or rented by the client (an expired reservation is not returned), in the order they were reserved
+    // paginated: skip offset cars and return at most limit cars
+    fn rented_by(env: Env, client: Identifier, offset: u32, limit: u32) -> Vec<CarInfo> {
+        let plates = active_rentals(&env, &client);
+        let mut cars = Vec::new(&env);
+        for plate in plates.iter().skip(offset as usize).take(limit as usize) {
+            cars.push_back(car_info(&env, plate.unwrap()));
+        }
+        cars
     }
 
-    // verify if car is rented
-    // return true if car is rented
-    fn has_rent(env: Env, plate: Bytes) -> bool {
-        has_rented_car(&env, &plate)
+    // return the plates of the cars reserved or rented by the client (an expired reservation is not active)
+    fn active(env: Env, client: Identifier) -> Vec<Bytes> {
+        active_rentals(&env, &client)
+    }
+
+    // return the tier of the client
+    fn clnt_tier(env: Env, client: Identifier) -> u32 {
+        read_tier(&env, &client)
+    }
+
+    // return the max number of cars reserved or rented at the same time by the clients of the tier
+    fn tier_limit(env: Env, tier: u32) -> u32 {
+        read_tier_limit(&env, tier)
+    }
+
+    // return the client requests waiting for approval, from the oldest
+    // paginated: skip offset requests and return at most limit requests
+    fn pending(env: Env, offset: u32, limit: u32) -> Vec<PendReq> {
+        let mut requests = Vec::new(&env);
+        for request in read_pending(&env)
+            .iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+        {
+            requests.push_back(request.unwrap());
+        }
+        requests
+    }
+
+    // return the approval expiration timestamp of the client (0 = no expiration)
+    fn appr_exp(env: Env, client: Identifier) -> u64 {
+        read_approval_expiry(&env, &client)
+    }
+
+    // return the approval validity period in seconds (0 = the approvals don't expire)
+    fn appr_ttl(env: Env) -> u64 {
+        read_approval_ttl(&env)
+    }
+
+    // return the charges owed by the client, not covered by the deposit nor transferred from its account
+    fn owed(env: Env, client: Identifier) -> i128 {
+        read_owed(&env, &client)
+    }
+
+    // return the reputation of the client: completed rentals, scored returns and the sum of the scores
+    fn reputation(env: Env, client: Identifier) -> Reputation {
+        read_reputation(&env, &client)
+    }
+
+    // return the ratings of the car: rated rentals and the sums of the car and service ratings
+    fn car_rating(env: Env, plate: Bytes) -> Rating {
+        let plate = canonical_plate(&env, &plate);
+        read_car_rating(&env, &plate)
+    }
+
+    // return the auto approval settings of open_req
+    fn auto_appr(env: Env) -> AutoAppr {
+        read_auto_approval(&env)
+    }
+
+    // return the loyalty discounts, sorted by the number of completed rentals
+    fn loyalty(env: Env) -> Vec<Loyalty> {
+        read_loyalty(&env)
+    }
+
+    // return the loyalty discount (basis points) of the client
+    fn discount(env: Env, client: Identifier) -> u32 {
+        loyalty_bps(&env, &client)
+    }
+
+    // return the final invoice of a completed rental (the rental id is informed in the history)
+    fn invoice(env: Env, id: u32) -> Invoice {
+        let rental = read_rental(&env, id);
+        if rental.outcome != Outcome::Completed {
+            panic_with_error!(&env, Error::RentalNotCompleted)
+        }
+
+        let total = rental.price
+            + rental.late_fee
+            + rental.km_fee
+            + rental.fuel_fee
+            + rental.premium
+            + rental.withheld
+            + rental.reloc;
+        Invoice {
+            id,
+            plate: rental.plate,
+            renter: rental.renter,
+            days: rental.days,
+            price: rental.price,
+            late_fee: rental.late_fee,
+            km: rental.km,
+            km_fee: rental.km_fee,
+            fuel_fee: rental.fuel_fee,
+            premium: rental.premium,
+            damage: rental.withheld,
+            covered: rental.covered,
+            reloc: rental.reloc,
+            total,
+            refunded: rental.refunded,
+        }
+    }
+
+    // return the closed rentals of the car, from the oldest
+    // paginated: skip offset rentals and return at most limit rentals
+    fn car_hist(env: Env, plate: Bytes, offset: u32, limit: u32) -> Vec<RentalRec> {
+        let plate = canonical_plate(&env, &plate);
+        rentals(&env, read_car_history(&env, &plate), offset, limit)
+    }
+
+    // return the closed rentals of the client, from the oldest
+    // paginated: skip offset rentals and return at most limit rentals
+    fn clnt_hist(env: Env, client: Identifier, offset: u32, limit: u32) -> Vec<RentalRec> {
+        rentals(&env, read_client_history(&env, &client), offset, limit)
+    }
+
+    // return the insurance product (premium and deductible)
+    fn insurance(env: Env, id: u32) -> Insurance {
+        read_insurance(&env, id)
+    }
+
+    // return the balance of the claims pool
+    fn pool(env: Env) -> i128 {
+        read_pool(&env)
+    }
+
+    // return the maintenance windows of the car, sorted by the start of the period
+    fn maint_wins(env: Env, plate: Bytes) -> Vec<MaintWin> {
+        let plate = canonical_plate(&env, &plate);
+        read_maint_wins(&env, &plate)
+    }
+
+    // return the completed services of the car, from the oldest
+    // paginated: skip offset services and return at most limit services
+    fn svc_hist(env: Env, plate: Bytes, offset: u32, limit: u32) -> Vec<ServiceRec> {
+        let plate = canonical_plate(&env, &plate);
+        let mut services = Vec::new(&env);
+        for service in read_services(&env, &plate)
+            .iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+        {
+            services.push_back(service.unwrap());
+        }
+        services
+    }
+
+    // return the usage of the car since its last service and if it is due for service
+    fn svc_state(env: Env, plate: Bytes) -> SvcState {
+        let plate = canonical_plate(&env, &plate);
+        service_state(&env, &plate)
+    }
+
+    // return the service rule
+    fn svc_rule(env: Env) -> SvcRule {
+        read_svc_rule(&env)
+    }
+
+    // return the branch codes, in the order they were added
+    fn branches(env: Env) -> Vec<Bytes> {
+        read_branches(&env)
+    }
+
+    // return the relocation fee of the one-way rentals returned at the branch
+    fn reloc_fee(env: Env, branch: Bytes) -> i128 {
+        read_branch(&env, &branch)
+    }
+
+    // return the pricing rules: weekend multiplier, seasons and surges
+    fn pricing(env: Env) -> Pricing {
+        Pricing {
+            weekend: read_weekend(&env),
+            seasons: read_seasons(&env),
+            surges: read_surges(&env),
+        }
+    }
+
+    // return the price breakdown of a rental of the car in the period [from, to) with the pricing rules in force
+    fn quote(env: Env, plate: Bytes, from: u64, to: u64) -> Quote {
+        let plate = canonical_plate(&env, &plate);
+        if to <= from {
+            panic_with_error!(&env, Error::InvalidDuration)
+        }
+        let car_data = read_car(&env, plate);
+        price_quote(&env, &car_data, from, started_days(from, to))
+    }
+
+    // return the reservation policy (ttl, free cancellation period and cancellation fee)
+    fn policy(env: Env) -> ResvPolicy {
+        read_policy(&env)
     }
 
     // return the admin identifier
     fn read_admin(env: Env) -> Identifier {
         read_admin(&env)
     }
+
+    // verify if the operator has the role. The owner (admin) has all the roles
+    fn has_role(env: Env, operator: Identifier, role: Role) -> bool {
+        has_role(&env, &operator, role)
+    }
+
+    // return the payment token contract id
+    fn token_id(env: Env) -> BytesN<32> {
+        read_token(&env)
+    }
+}
+
+// Car data with its rental status. A free car in a maintenance window is in maintenance
+fn car_info(env: &Env, plate: Bytes) -> CarInfo {
+    let car = read_car(env, plate.clone());
+    let now = env.ledger().timestamp();
+    let in_window = overlaps_maint(env, &plate, now, now + 1);
+    let status = if !has_rented_car(env, &plate) {
+        if car.maint || in_window {
+            CarStatus::Maintenance
+        } else {
+            CarStatus::Available
+        }
+    } else {
+        let rented_car_data = read_rented_car(env, &plate);
+        if is_reservation_expired(env, &rented_car_data) {
+            if car.maint || in_window {
+                CarStatus::Maintenance
+            } else {
+                CarStatus::Available
+            }
+        } else {
+            match rented_car_data.status {
+                RentedCarStatus::Reserved => CarStatus::Reserved,
+                RentedCarStatus::Rented => CarStatus::Rented,
+                RentedCarStatus::DropReview => CarStatus::DropReview,
+                RentedCarStatus::DropReviewDenied => CarStatus::DropReviewDenied,
+                RentedCarStatus::InDispute => CarStatus::InDispute,
+            }
+        }
+    };
+
+    let svc_due = service_state(env, &plate).due;
+    CarInfo {
+        car,
+        plate,
+        status,
+        svc_due,
+    }
+}
+
+// The client must be approved to reserve or book a car, and must not owe charges of a previous rental
+fn check_client_authorized(env: &Env, client: &Identifier) {
+    if !is_client_authorized(env, client) {
+        if read_client(env, client.clone()) == ClientStatus::Unknown {
+            panic_with_error!(env, Error::ClientNotFound)
+        }
+        panic_with_error!(env, Error::ClientNotAuthorized)
+    }
+    if read_owed(env, client) > 0 {
+        panic_with_error!(env, Error::OutstandingBalance)
+    }
+}
+
+// A client request (pending or declined) can be approved or declined.
+// An approved client can be declined too, but a suspended or revoked client changes with reinstate or revoke
+fn check_client_request(env: &Env, client: &Identifier) {
+    match read_client(env, client.clone()) {
+        ClientStatus::Unknown => panic_with_error!(env, Error::ClientNotFound),
+        ClientStatus::Suspended | ClientStatus::Revoked => {
+            panic_with_error!(env, Error::InvalidClientStatus)
+        }
+        _ => {}
+    }
+}
+
+// Approve the client until the end of the approval validity period
+fn approve_client(env: &Env, client: &Identifier) {
+    let ttl = read_approval_ttl(env);
+    let expires = if ttl == 0 {
+        0
+    } else {
+        env.ledger().timestamp() + ttl
+    };
+    write_approval_expiry(env, client, expires);
+    write_client(env, client.clone(), ClientStatus::Approved);
+    event::appr_req(env, client.clone());
+}
+
+fn decline_client(env: &Env, client: &Identifier) {
+    write_client(env, client.clone(), ClientStatus::Declined);
+    event::deny_req(env, client.clone());
+}
+
+// Plates reserved or rented by the client, without the expired reservations
+fn active_rentals(env: &Env, client: &Identifier) -> Vec<Bytes> {
+    let mut plates = Vec::new(env);
+    for plate in read_active(env, client).iter() {
+        let plate = plate.unwrap();
+        if !is_reservation_expired(env, &read_rented_car(env, &plate)) {
+            plates.push_back(plate);
+        }
+    }
+    plates
+}
+
+// Cars held by the client: the active rentals and the bookings not ended
+fn held_cars(env: &Env, client: &Identifier) -> u32 {
+    let now = env.ledger().timestamp();
+    let mut held = active_rentals(env, client).len();
+    for plate in read_booked(env, client).iter() {
+        for booking in read_bookings(env, &plate.unwrap()).iter() {
+            let booking = booking.unwrap();
+            if booking.renter == *client && now < booking.end {
+                held += 1;
+            }
+        }
+    }
+    held
+}
+
+// The client can't hold more cars than the limit of its tier
+fn check_rental_limit(env: &Env, client: &Identifier, held: u32) {
+    if held >= read_tier_limit(env, read_tier(env, client)) {
+        panic_with_error!(env, Error::RentalLimitReached)
+    }
+}
+
+// A reservation is expired when the car was not taken until the expiration timestamp
+fn is_reservation_expired(env: &Env, rented_car_data: &RentedCarDataKey) -> bool {
+    rented_car_data.status == RentedCarStatus::Reserved
+        && env.ledger().timestamp() >= rented_car_data.expires
+}
+
+// Release a reserved car. The cancellation fee is withheld from the deposit
+// and the rest and the insurance premium are refunded to the client. Return the released reservation with the refunded deposit
+fn release_reservation(env: &Env, plate: &Bytes, outcome: Outcome) -> RentedCarDataKey {
+    let mut rented_car_data = read_rented_car(env, plate);
+    refund_premium(env, &mut rented_car_data);
+    withhold_fee(env, &mut rented_car_data);
+    transfer_from_contract_to_account(env, &rented_car_data.renter, &rented_car_data.deposit);
+    remove_rented_car(env, plate);
+    close_rental(env, plate, &rented_car_data, outcome);
+    rented_car_data
+}
+
+// Insurance product of a reservation or booking, 0 without insurance
+fn insurance_coverage(env: &Env, insurance: u32) -> Insurance {
+    if insurance == 0 {
+        return Insurance {
+            premium: 0,
+            deduct: 0,
+        };
+    }
+    read_insurance(env, insurance)
+}
+
+// Refund the insurance premium of a rental not taken (cancelled or expired) from the claims pool, up to its balance.
+// The premium kept in the rental is the part not refunded
+fn refund_premium(env: &Env, rented_car_data: &mut RentedCarDataKey) {
+    let pool = read_pool(env);
+    let refund = if rented_car_data.premium < pool {
+        rented_car_data.premium
+    } else {
+        pool
+    };
+    if refund <= 0 {
+        return;
+    }
+    write_pool(env, pool - refund);
+    transfer_from_contract_to_account(env, &rented_car_data.renter, &refund);
+    rented_car_data.premium -= refund;
+}
+
+// Withhold the cancellation fee (up to the deposit) to the admin
+fn withhold_fee(env: &Env, rented_car_data: &mut RentedCarDataKey) {
+    let fee = read_policy(env).fee;
+    let withheld = if fee < rented_car_data.deposit {
+        fee
+    } else {
+        rented_car_data.deposit
+    };
+
+    transfer_from_contract_to_account(env, &read_admin(env), &withheld);
+    rented_car_data.deposit -= withheld;
+    rented_car_data.withheld += withheld;
+}
+
+// true if a rental of the car was taken and not closed at some time of the period [start, end).
+// The car history is in closing order, so it is read from the newest until a rental closed before the period
+fn held_in_period(env: &Env, plate: &Bytes, start: u64, end: u64) -> bool {
+    if has_rented_car(env, plate) {
+        let rented_car_data = read_rented_car(env, plate);
+        if rented_car_data.status != RentedCarStatus::Reserved && rented_car_data.picked_up < end {
+            return true;
+        }
+    }
+    let history = read_car_history(env, plate);
+    for index in (0..history.len()).rev() {
+        let rental = read_rental(env, history.get_unchecked(index).unwrap());
+        if rental.closed <= start {
+            break;
+        }
+        if rental.picked_up != 0 && rental.picked_up < end {
+            return true;
+        }
+    }
+    false
+}
+
+// Release a booking already removed from the car bookings, withholding the cancellation fee if informed.
+// The insurance premium is refunded. Return the refunded deposit
+fn release_booking(
+    env: &Env,
+    plate: &Bytes,
+    booking: &Booking,
+    outcome: Outcome,
+    with_fee: bool,
+) -> i128 {
+    let mut rented_car_data = booking_rental(env, booking);
+    refund_premium(env, &mut rented_car_data);
+    if with_fee {
+        withhold_fee(env, &mut rented_car_data);
+    }
+    transfer_from_contract_to_account(env, &booking.renter, &rented_car_data.deposit);
+    close_rental(env, plate, &rented_car_data, outcome);
+    rented_car_data.deposit
+}
+
+// Started days of the period [start, end)
+fn started_days(start: u64, end: u64) -> u32 {
+    (end - start).div_ceil(DAY_IN_SECONDS) as u32
+}
+
+// Price of a rental of days from the timestamp informed. Each day starting on a weekend or in a season
+// has its daily rate multiplied, and the surge multiplier of the share of reserved or rented cars
+// is applied to the whole rental. The multipliers are in basis points and the amounts are rounded down.
+fn price_quote(env: &Env, car_data: &CarDataKey, from: u64, days: u32) -> Quote {
+    let bps = BASIS_POINTS as i128;
+    let weekend_bps = read_weekend(env) as i128;
+    let seasons = read_seasons(env);
+
+    let mut weekend = 0;
+    let mut season = 0;
+    for day in 0..days {
+        let day_start = from + (day as u64) * DAY_IN_SECONDS;
+        // the epoch (day 0) is a thursday, so the day of the week is (day + 4) % 7 with sunday as 0
+        let weekday = (day_start / DAY_IN_SECONDS + 4) % 7;
+        let mut rate = car_data.daily_rate;
+        if weekday == 0 || weekday == 6 {
+            let extra = rate * weekend_bps / bps - rate;
+            weekend += extra;
+            rate += extra;
+        }
+        for s in seasons.iter() {
+            let s = s.unwrap();
+            if s.start <= day_start && day_start < s.end {
+                season += rate * (s.bps as i128) / bps - rate;
+                break;
+            }
+        }
+    }
+
+    let base = car_data.daily_rate * (days as i128);
+    let subtotal = base + weekend + season;
+    let util = utilization(env);
+    let mut surge_bps = bps;
+    for s in read_surges(env).iter() {
+        let s = s.unwrap();
+        if s.util <= util {
+            surge_bps = s.bps as i128;
+        }
+    }
+    let surge = subtotal * surge_bps / bps - subtotal;
+
+    Quote {
+        days,
+        base,
+        weekend,
+        season,
+        util,
+        surge,
+        total: subtotal + surge,
+    }
+}
+
+// Share of the cars reserved or rented (%). An expired reservation not released yet is not counted
+fn utilization(env: &Env) -> u32 {
+    let plates = read_plates(env);
+    if plates.is_empty() {
+        return 0;
+    }
+    let mut rented = 0;
+    for plate in plates.iter() {
+        let plate = plate.unwrap();
+        if has_rented_car(env, &plate)
+            && !is_reservation_expired(env, &read_rented_car(env, &plate))
+        {
+            rented += 1;
+        }
+    }
+    rented * 100 / plates.len()
+}
+
+// Rented car data of a booking. The rental days are the started days of the period
+fn booking_rental(env: &Env, booking: &Booking) -> RentedCarDataKey {
+    RentedCarDataKey {
+        renter: booking.renter.clone(),
+        status: RentedCarStatus::Reserved,
+        days: started_days(booking.start, booking.end),
+        deposit: booking.deposit,
+        reason: 0,
+        reserved: booking.booked,
+        expires: booking.end,
+        picked_up: 0,
+        due: booking.end,
+        returned: 0,
+        late_fee: 0,
+        denials: Vec::new(env),
+        withheld: 0,
+        dmg_cost: 0,
+        evidence: BytesN::from_array(env, &[0; 32]),
+        price: booking.price,
+        odo_out: 0,
+        fuel_out: 0,
+        odo_in: 0,
+        fuel_in: 0,
+        km_fee: 0,
+        fuel_fee: 0,
+        insurance: booking.insurance,
+        premium: booking.premium,
+        deduct: booking.deduct,
+        covered: 0,
+        pickup: booking.pickup.clone(),
+        dropoff: booking.dropoff.clone(),
+        reloc: booking.reloc,
+    }
+}
+
+// true if the branch is the home or current branch of a car,
+// or the pickup or drop-off branch of a reservation or booking
+fn is_branch_in_use(env: &Env, branch: &Bytes) -> bool {
+    for plate in read_plates(env).iter() {
+        let plate = plate.unwrap();
+        let car_data = read_car(env, plate.clone());
+        if car_data.home == *branch || car_data.location == *branch {
+            return true;
+        }
+        if has_rented_car(env, &plate) {
+            let rented_car_data = read_rented_car(env, &plate);
+            if rented_car_data.pickup == *branch || rented_car_data.dropoff == *branch {
+                return true;
+            }
+        }
+        for booking in read_bookings(env, &plate).iter() {
+            let booking = booking.unwrap();
+            if booking.pickup == *branch || booking.dropoff == *branch {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+// Relocation fee of a rental: the fee of the drop-off branch for a one-way rental, 0 for a round trip
+fn relocation_fee(env: &Env, pickup: &Bytes, dropoff: &Bytes) -> i128 {
+    let reloc = read_branch(env, dropoff);
+    if pickup == dropoff {
+        return 0;
+    }
+    reloc
+}
+
+// true if the client has a booking of the car
+fn has_booking(env: &Env, plate: &Bytes, client: &Identifier) -> bool {
+    for booking in read_bookings(env, plate).iter() {
+        if booking.unwrap().renter == *client {
+            return true;
+        }
+    }
+    false
+}
+
+// Insert the item at the index, moving the next items.
+// The Vec::insert of the sdk replaces the item at the index instead of inserting it.
+fn insert_at<T>(vec: &mut Vec<T>, index: u32, item: T)
+where
+    T: IntoVal<Env, RawVal> + TryFromVal<Env, RawVal>,
+{
+    let tail = vec.slice(index..);
+    *vec = vec.slice(..index);
+    vec.push_back(item);
+    vec.append(&tail);
+}
+
+// Remove from the car bookings the booking of the client in its period
+fn take_booking(env: &Env, plate: &Bytes, client: &Identifier, now: u64) -> Booking {
+    let mut bookings = read_bookings(env, plate);
+    let mut index = None;
+    for (i, booking) in bookings.iter().enumerate() {
+        let booking = booking.unwrap();
+        if booking.renter == *client && booking.start <= now && now < booking.end {
+            index = Some(i as u32);
+            break;
+        }
+    }
+    let index = match index {
+        Some(index) => index,
+        None => panic_with_error!(env, Error::NotInBookingWindow),
+    };
+    let booking = bookings.get_unchecked(index).unwrap();
+    bookings.remove(index);
+    write_bookings(env, plate, bookings);
+    booking
+}
+
+// true if the period [start, end) overlaps a maintenance window of the car
+fn overlaps_maint(env: &Env, plate: &Bytes, start: u64, end: u64) -> bool {
+    for window in read_maint_wins(env, plate).iter() {
+        let window = window.unwrap();
+        if start < window.end && window.start < end {
+            return true;
+        }
+    }
+    false
+}
+
+// Usage of the car since its last service, flagged with the service rule in force
+fn service_state(env: &Env, plate: &Bytes) -> SvcState {
+    let mut state = read_svc_state(env, plate);
+    state.due = is_service_due(env, &state);
+    state
+}
+
+// true if the usage reaches the completed rentals or the km of the service rule
+fn is_service_due(env: &Env, state: &SvcState) -> bool {
+    let rule = read_svc_rule(env);
+    (rule.rentals > 0 && state.rentals >= rule.rentals) || (rule.km > 0 && state.km >= rule.km)
+}
+
+// true if the period [start, end) overlaps a booking of the car
+fn overlaps_booking(env: &Env, plate: &Bytes, start: u64, end: u64) -> bool {
+    for booking in read_bookings(env, plate).iter() {
+        let booking = booking.unwrap();
+        if start < booking.end && booking.start < end {
+            return true;
+        }
+    }
+    false
+}
+
+// true if the car is free in the period [start, end): after the current reservation or rental and without bookings
+fn is_period_free(env: &Env, plate: &Bytes, start: u64, end: u64) -> bool {
+    start >= busy_until(env, plate) && !overlaps_booking(env, plate, start, end)
+}
+
+// Timestamp until the car is held by the current reservation or rental (0 if the car is free).
+// A reservation holds the car until the latest return, taking the car just before expiring
+fn busy_until(env: &Env, plate: &Bytes) -> u64 {
+    if !has_rented_car(env, plate) {
+        return 0;
+    }
+    let rented_car_data = read_rented_car(env, plate);
+    if is_reservation_expired(env, &rented_car_data) {
+        return 0;
+    }
+    match rented_car_data.status {
+        RentedCarStatus::Reserved => {
+            rented_car_data.expires + (rented_car_data.days as u64) * DAY_IN_SECONDS
+        }
+        RentedCarStatus::Rented => rented_car_data.due,
+        _ => rented_car_data.returned,
+    }
+}
+
+// Rented car data of a denied drop of the client
+fn read_denied_drop(env: &Env, plate: &Bytes, client: &Identifier) -> RentedCarDataKey {
+    // check if car is rented
+    if !has_rented_car(env, plate) {
+        panic_with_error!(env, Error::CarIsNotRented)
+    }
+
+    let rented_car_data = read_rented_car(env, plate);
+    // check if the renter is the same client that are answering the damage report
+    if rented_car_data.renter != *client {
+        panic_with_error!(env, Error::ClientIsNotRenter)
+    }
+    if rented_car_data.status != RentedCarStatus::DropReviewDenied {
+        panic_with_error!(env, Error::DropIsNotDenied)
+    }
+    rented_car_data
+}
+
+// Withhold the damage amount from the deposit to the admin. The car goes back to DropReview
+// The claims pool pays the damages above the deductible of an insured rental, and the rest is withheld from the deposit
+fn withhold_damage(env: &Env, plate: &Bytes, rented_car_data: &mut RentedCarDataKey, amount: i128) {
+    let covered = claim(env, rented_car_data, amount);
+    if covered > 0 {
+        write_pool(env, read_pool(env) - covered);
+        rented_car_data.covered += covered;
+        event::claim(env, plate.clone(), rented_car_data.renter.clone(), covered);
+    }
+    // the pool may have paid less than expected at the report, the deposit covers up to its balance
+    let mut withheld = amount - covered;
+    if withheld > rented_car_data.deposit {
+        withheld = rented_car_data.deposit;
+    }
+    transfer_from_contract_to_account(env, &read_admin(env), &(covered + withheld));
+    rented_car_data.deposit -= withheld;
+    rented_car_data.withheld += withheld;
+    rented_car_data.dmg_cost = 0;
+    rented_car_data.status = RentedCarStatus::DropReview;
+}
+
+// Part of the damages paid by the claims pool: the amount above the deductible, up to the pool balance
+fn claim(env: &Env, rented_car_data: &RentedCarDataKey, amount: i128) -> i128 {
+    if rented_car_data.insurance == 0 || amount <= rented_car_data.deduct {
+        return 0;
+    }
+    let covered = amount - rented_car_data.deduct;
+    let pool = read_pool(env);
+    if covered > pool {
+        pool
+    } else {
+        covered
+    }
+}
+
+// Keep the closed rental in the history of the car and of the client. A completed rental counts for the loyalty discounts
+fn close_rental(env: &Env, plate: &Bytes, rented_car_data: &RentedCarDataKey, outcome: Outcome) {
+    if outcome == Outcome::Completed {
+        let mut reputation = read_reputation(env, &rented_car_data.renter);
+        reputation.rentals += 1;
+        write_reputation(env, &rented_car_data.renter, reputation);
+    }
+    add_rental(
+        env,
+        RentalRec {
+            id: 0,
+            plate: plate.clone(),
+            renter: rented_car_data.renter.clone(),
+            outcome,
+            days: rented_car_data.days,
+            reserved: rented_car_data.reserved,
+            picked_up: rented_car_data.picked_up,
+            due: rented_car_data.due,
+            returned: rented_car_data.returned,
+            closed: env.ledger().timestamp(),
+            denials: rented_car_data.denials.clone(),
+            withheld: rented_car_data.withheld,
+            late_fee: rented_car_data.late_fee,
+            price: rented_car_data.price,
+            km: driven_km(rented_car_data),
+            km_fee: rented_car_data.km_fee,
+            fuel_fee: rented_car_data.fuel_fee,
+            refunded: rented_car_data.deposit,
+            insurance: rented_car_data.insurance,
+            premium: rented_car_data.premium,
+            covered: rented_car_data.covered,
+            score: 0,
+            car_rate: 0,
+            svc_rate: 0,
+            pickup: rented_car_data.pickup.clone(),
+            dropoff: rented_car_data.dropoff.clone(),
+            reloc: rented_car_data.reloc,
+        },
+    )
+}
+
+// Scores and ratings go from 1 to 5
+fn check_score(env: &Env, score: u32) {
+    if !(1..=5).contains(&score) {
+        panic_with_error!(env, Error::InvalidScore)
+    }
+}
+
+// true if the client has the scored returns and the average score required by the auto approval
+fn has_good_reputation(env: &Env, client: &Identifier) -> bool {
+    let auto_approval = read_auto_approval(env);
+    let reputation = read_reputation(env, client);
+    auto_approval.count > 0
+        && reputation.scores >= auto_approval.count
+        && reputation.total * 100 >= auto_approval.score * reputation.scores
+}
+
+// Loyalty discount (basis points) of the highest number of completed rentals reached by the client
+fn loyalty_bps(env: &Env, client: &Identifier) -> u32 {
+    let rentals = read_reputation(env, client).rentals;
+    let mut bps = 0;
+    for discount in read_loyalty(env).iter() {
+        let discount = discount.unwrap();
+        if discount.rentals <= rentals {
+            bps = discount.bps;
+        }
+    }
+    bps
+}
+
+// Loyalty discount of the client on the price informed (rounded down)
+fn loyalty_discount(env: &Env, client: &Identifier, price: i128) -> i128 {
+    price * (loyalty_bps(env, client) as i128) / (BASIS_POINTS as i128)
+}
+
+// Rentals of the history informed, paginated: skip offset rentals and return at most limit rentals
+fn rentals(env: &Env, ids: Vec<u32>, offset: u32, limit: u32) -> Vec<RentalRec> {
+    let mut rentals = Vec::new(env);
+    for id in ids.iter().skip(offset as usize).take(limit as usize) {
+        rentals.push_back(read_rental(env, id.unwrap()));
+    }
+    rentals
+}
+
+// Late fee of a rental returned at the timestamp informed.
+// No fee until the end of the grace period, then every hour (or part of it) after the grace period is charged
+fn late_fee(car_data: &CarDataKey, rented_car_data: &RentedCarDataKey, returned: u64) -> i128 {
+    let late_from = rented_car_data.due + car_data.grace;
+    if returned <= late_from {
+        return 0;
+    }
+    let late_hours = (returned - late_from).div_ceil(HOUR_IN_SECONDS);
+    car_data.late_rate * (late_hours as i128)
+}
+
+// Canonical form of a plate: the letters in upper case and the separators (space, '-' and '.') removed,
+// e.g. "abc-1234" and "ABC 1234" are the plate ABC1234.
+// Only letters and digits are allowed, and the canonical plate has from 1 to PLATE_MAX_LEN characters
+fn canonical_plate(env: &Env, plate: &Bytes) -> Bytes {
+    let mut canonical = Bytes::new(env);
+    for c in plate.iter() {
+        match c {
+            b' ' | b'-' | b'.' => {}
+            b'A'..=b'Z' | b'0'..=b'9' => canonical.push(c),
+            b'a'..=b'z' => canonical.push(c.to_ascii_uppercase()),
+            _ => panic_with_error!(env, Error::InvalidPlate),
+        }
+    }
+    if canonical.is_empty() || canonical.len() > PLATE_MAX_LEN {
+        panic_with_error!(env, Error::InvalidPlate)
+    }
+    canonical
+}
+
+// Canonical plate of a plate stored before the canonicalization.
+// The characters other than letters and digits are dropped like the separators
+fn legacy_canonical_plate(env: &Env, plate: &Bytes) -> Bytes {
+    let mut stripped = Bytes::new(env);
+    for c in plate.iter() {
+        if c.is_ascii_alphanumeric() {
+            stripped.push(c);
+        }
+    }
+    canonical_plate(env, &stripped)
+}
+
+// The fuel level is a percentage and the odometer can't go back
+fn check_reading(env: &Env, odometer: u64, fuel: u32, min_odometer: u64) {
+    if odometer < min_odometer || fuel > 100 {
+        panic_with_error!(env, Error::InvalidReading)
+    }
+}
+
+// Km driven in the rental (0 while the car is not returned)
+fn driven_km(rented_car_data: &RentedCarDataKey) -> u64 {
+    if rented_car_data.odo_in < rented_car_data.odo_out {
+        return 0;
+    }
+    rented_car_data.odo_in - rented_car_data.odo_out
+}
+
+// Every km above the mileage allowance (allowance per day * rental days) is charged with the car km rate
+fn km_fee(car_data: &CarDataKey, rented_car_data: &RentedCarDataKey) -> i128 {
+    let allowance = car_data.km_day * (rented_car_data.days as u64);
+    let km = driven_km(rented_car_data);
+    if km <= allowance {
+        return 0;
+    }
+    car_data.km_rate * ((km - allowance) as i128)
+}
+
+// Every fuel level point missing from the pickup level is charged with the car fuel rate
+fn fuel_fee(car_data: &CarDataKey, rented_car_data: &RentedCarDataKey) -> i128 {
+    if rented_car_data.fuel_in >= rented_car_data.fuel_out {
+        return 0;
+    }
+    car_data.fuel_rate * ((rented_car_data.fuel_out - rented_car_data.fuel_in) as i128)
+}
+
+// Charge the renter to the admin. The amount is taken from the deposit in escrow first,
+// and the rest is transferred from the renter account, up to its allowance and balance.
+// What can't be transferred is owed by the renter
+fn charge_renter(env: &Env, rented_car_data: &mut RentedCarDataKey, amount: i128) {
+    let admin = read_admin(env);
+    let from_deposit = if amount < rented_car_data.deposit {
+        amount
+    } else {
+        rented_car_data.deposit
+    };
+
+    transfer_from_contract_to_account(env, &admin, &from_deposit);
+    rented_car_data.deposit -= from_deposit;
+
+    let rest = amount - from_deposit;
+    if rest == 0 {
+        return;
+    }
+    let client = token::Client::new(env, read_token(env));
+    let renter = &rented_car_data.renter;
+    let allowance = client.allowance(renter, &Identifier::Contract(env.current_contract()));
+    let from_account = rest.min(allowance).min(client.balance(renter)).max(0);
+    transfer_from_account_to_account(env, renter, &admin, &from_account);
+    if from_account < rest {
+        let owed = read_owed(env, renter) + rest - from_account;
+        write_owed(env, renter, owed);
+        event::owed(env, renter.clone(), owed);
+    }
+}
+
+fn transfer_from_contract_to_account(env: &Env, to: &Identifier, amount: &i128) {
+    if *amount == 0 {
+        return;
+    }
+    let client = token::Client::new(env, read_token(env));
+    client.xfer(&Signature::Invoker, &0, to, amount);
+}
+
+fn transfer_from_account_to_contract(env: &Env, from: &Identifier, amount: &i128) {
+    transfer_from_account_to_account(
+        env,
+        from,
+        &Identifier::Contract(env.current_contract()),
+        amount,
+    );
+}
+
+fn transfer_from_account_to_account(env: &Env, from: &Identifier, to: &Identifier, amount: &i128) {
+    if *amount == 0 {
+        return;
+    }
+    let client = token::Client::new(env, read_token(env));
+    client.xfer_from(&Signature::Invoker, &0, from, to, amount);
 }
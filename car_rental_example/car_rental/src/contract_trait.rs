@@ -1,37 +1,147 @@
-use crate::storage_types::{CarDataKey, ClientStatus, RentedCarDataKey};
+use crate::storage_types::{
+    AutoAppr, Booking, CarDataKey, CarInfo, CarSpec, ClientStatus, Insurance, Invoice, Loyalty,
+    MaintWin, PendReq, Pricing, Quote, Rating, RentalRec, RentedCarDataKey, Reputation, ResvPolicy,
+    Role, ServiceRec, SvcRule, SvcState,
+};
 use soroban_auth::{Identifier, Signature};
-use soroban_sdk::{BigInt, Bytes, Env};
+use soroban_sdk::{Bytes, BytesN, Env, Vec};
 
 /*
 This trait represents the basic structure of this contract.
 It's function implementation are defined at file contract.
-Every function (fn) that contains ADMIN EXPECTED means it's necessary the admin to execute it (admin signature).
+Every function (fn) that contains ADMIN EXPECTED means it's necessary an operator with the role informed to execute it (operator signature).
+The admin is the owner of the contract and has all the roles.
 */
 pub trait CarRentalTrait {
-    fn init(env: Env, admin: Identifier);
-    fn add_car(
+    fn init(env: Env, admin: Identifier, token_id: BytesN<32>);
+    fn add_car(env: Env, admin: Signature, nonce: i128, plate: Bytes, car: CarSpec);
+    fn update_car(env: Env, admin: Signature, nonce: i128, plate: Bytes, car: CarSpec);
+    fn set_maint(env: Env, admin: Signature, nonce: i128, plate: Bytes, maint: bool);
+    fn remove_car(env: Env, admin: Signature, nonce: i128, plate: Bytes);
+    fn migr_plate(env: Env, admin: Signature, nonce: i128, plate: Bytes);
+    fn set_late(env: Env, admin: Signature, nonce: i128, plate: Bytes, grace: u64, late_rate: i128);
+    fn set_usage(
         env: Env,
         admin: Signature,
-        nonce: BigInt,
+        nonce: i128,
         plate: Bytes,
-        model: Bytes,
-        color: Bytes,
-        horse: i32,
+        km_rate: i128,
+        km_day: u64,
+        fuel_rate: i128,
     );
-    fn remove_car(env: Env, admin: Signature, nonce: BigInt, plate: Bytes);
-    fn appr_req(env: Env, admin: Signature, client: Identifier, nonce: BigInt);
-    fn deny_req(env: Env, admin: Signature, client: Identifier, nonce: BigInt);
-    fn open_req(env: Env, client: Signature, nonce: BigInt);
-    fn take_car(env: Env, client: Signature, nonce: BigInt, plate: Bytes);
+    fn set_branch(env: Env, admin: Signature, nonce: i128, branch: Bytes, reloc: i128);
+    fn rm_branch(env: Env, admin: Signature, nonce: i128, branch: Bytes);
+    fn reloc_car(env: Env, admin: Signature, nonce: i128, plate: Bytes, branch: Bytes);
+    fn add_maint(env: Env, admin: Signature, nonce: i128, plate: Bytes, start: u64, end: u64);
+    fn rm_maint(env: Env, admin: Signature, nonce: i128, plate: Bytes, start: u64);
+    fn svc_done(env: Env, admin: Signature, nonce: i128, plate: Bytes, mileage: u64, cost: i128);
+    fn set_svc(env: Env, admin: Signature, nonce: i128, rentals: u32, km: u64);
+    fn set_policy(env: Env, admin: Signature, nonce: i128, ttl: u64, free: u64, fee: i128);
+    fn add_insur(env: Env, admin: Signature, nonce: i128, premium: i128, deduct: i128) -> u32;
+    fn set_wkend(env: Env, admin: Signature, nonce: i128, bps: u32);
+    fn add_season(env: Env, admin: Signature, nonce: i128, start: u64, end: u64, bps: u32);
+    fn rm_season(env: Env, admin: Signature, nonce: i128, start: u64);
+    fn set_surge(env: Env, admin: Signature, nonce: i128, util: u32, bps: u32);
+    fn appr_req(env: Env, admin: Signature, client: Identifier, nonce: i128);
+    fn deny_req(env: Env, admin: Signature, client: Identifier, nonce: i128);
+    fn batch_req(
+        env: Env,
+        admin: Signature,
+        nonce: i128,
+        approve: Vec<Identifier>,
+        deny: Vec<Identifier>,
+    );
+    fn set_valid(env: Env, admin: Signature, nonce: i128, ttl: u64);
+    fn suspend(env: Env, admin: Signature, client: Identifier, nonce: i128);
+    fn reinstate(env: Env, admin: Signature, client: Identifier, nonce: i128);
+    fn revoke(env: Env, admin: Signature, client: Identifier, nonce: i128);
+    fn open_req(env: Env, client: Signature, nonce: i128, kyc: BytesN<32>);
+    fn rate_rent(env: Env, client: Signature, nonce: i128, id: u32, car: u32, service: u32);
+    fn take_car(env: Env, client: Signature, nonce: i128, plate: Bytes, odometer: u64, fuel: u32);
     fn read_car(env: Env, plate: Bytes) -> CarDataKey;
-    fn resrve_car(env: Env, client: Signature, nonce: BigInt, plate: Bytes);
-    fn drop_car(env: Env, client: Signature, nonce: BigInt, plate: Bytes);
-    fn accpt_drop(env: Env, admin: Signature, nonce: BigInt, plate: Bytes);
-    fn deny_drop(env: Env, admin: Signature, nonce: BigInt, plate: Bytes);
+    fn resrve_car(
+        env: Env,
+        client: Signature,
+        nonce: i128,
+        plate: Bytes,
+        days: u32,
+        insurance: u32,
+        dropoff: Bytes,
+    );
+    fn cancl_resv(env: Env, client: Signature, nonce: i128, plate: Bytes);
+    fn book_car(
+        env: Env,
+        client: Signature,
+        nonce: i128,
+        plate: Bytes,
+        start: u64,
+        end: u64,
+        insurance: u32,
+        pickup: Bytes,
+        dropoff: Bytes,
+    );
+    fn cancl_book(env: Env, client: Signature, nonce: i128, plate: Bytes, start: u64);
+    fn rls_expird(env: Env, plate: Bytes);
+    fn drop_car(env: Env, client: Signature, nonce: i128, plate: Bytes, odometer: u64, fuel: u32);
+    fn accpt_drop(env: Env, admin: Signature, nonce: i128, plate: Bytes, odometer: u64, fuel: u32);
+    fn deny_drop(
+        env: Env,
+        admin: Signature,
+        nonce: i128,
+        plate: Bytes,
+        reason: u32,
+        cost: i128,
+        evidence: BytesN<32>,
+    );
+    fn accpt_dmg(env: Env, client: Signature, nonce: i128, plate: Bytes);
+    fn dispute(env: Env, client: Signature, nonce: i128, plate: Bytes);
+    fn pay_owed(env: Env, client: Signature, nonce: i128);
+    fn settle(env: Env, admin: Signature, nonce: i128, plate: Bytes, amount: i128);
     fn read_clnt(env: Env, client: Identifier) -> ClientStatus;
-    fn nonce(env: Env, identifier: Identifier) -> BigInt;
+    fn nonce(env: Env, identifier: Identifier) -> i128;
     fn read_rent(env: Env, plate: Bytes) -> RentedCarDataKey;
     fn has_rent(env: Env, plate: Bytes) -> bool;
-    fn set_admin(env: Env, admin: Signature, nonce: BigInt, new_admin: Identifier);
+    fn charges(env: Env, plate: Bytes) -> i128;
+    fn bookings(env: Env, plate: Bytes) -> Vec<Booking>;
+    fn available(env: Env, plate: Bytes, from: u64, to: u64) -> bool;
+    fn list_cars(env: Env, offset: u32, limit: u32) -> Vec<CarInfo>;
+    fn list_avail(env: Env, branch: Bytes, offset: u32, limit: u32) -> Vec<CarInfo>;
+    fn rented_by(env: Env, client: Identifier, offset: u32, limit: u32) -> Vec<CarInfo>;
+    fn car_hist(env: Env, plate: Bytes, offset: u32, limit: u32) -> Vec<RentalRec>;
+    fn clnt_hist(env: Env, client: Identifier, offset: u32, limit: u32) -> Vec<RentalRec>;
+    fn active(env: Env, client: Identifier) -> Vec<Bytes>;
+    fn clnt_tier(env: Env, client: Identifier) -> u32;
+    fn tier_limit(env: Env, tier: u32) -> u32;
+    fn pending(env: Env, offset: u32, limit: u32) -> Vec<PendReq>;
+    fn appr_exp(env: Env, client: Identifier) -> u64;
+    fn appr_ttl(env: Env) -> u64;
+    fn owed(env: Env, client: Identifier) -> i128;
+    fn reputation(env: Env, client: Identifier) -> Reputation;
+    fn car_rating(env: Env, plate: Bytes) -> Rating;
+    fn auto_appr(env: Env) -> AutoAppr;
+    fn loyalty(env: Env) -> Vec<Loyalty>;
+    fn discount(env: Env, client: Identifier) -> u32;
+    fn invoice(env: Env, id: u32) -> Invoice;
+    fn insurance(env: Env, id: u32) -> Insurance;
+    fn pool(env: Env) -> i128;
+    fn maint_wins(env: Env, plate: Bytes) -> Vec<MaintWin>;
+    fn svc_hist(env: Env, plate: Bytes, offset: u32, limit: u32) -> Vec<ServiceRec>;
+    fn svc_state(env: Env, plate: Bytes) -> SvcState;
+    fn svc_rule(env: Env) -> SvcRule;
+    fn branches(env: Env) -> Vec<Bytes>;
+    fn reloc_fee(env: Env, branch: Bytes) -> i128;
+    fn pricing(env: Env) -> Pricing;
+    fn quote(env: Env, plate: Bytes, from: u64, to: u64) -> Quote;
+    fn policy(env: Env) -> ResvPolicy;
+    fn score_ret(env: Env, admin: Signature, nonce: i128, id: u32, score: u32);
+    fn set_admin(env: Env, admin: Signature, nonce: i128, new_admin: Identifier);
     fn read_admin(env: Env) -> Identifier;
+    fn set_tier(env: Env, admin: Signature, nonce: i128, client: Identifier, tier: u32);
+    fn set_limit(env: Env, admin: Signature, nonce: i128, tier: u32, limit: u32);
+    fn set_auto(env: Env, admin: Signature, nonce: i128, score: u32, count: u32);
+    fn set_loyal(env: Env, admin: Signature, nonce: i128, rentals: u32, bps: u32);
+    fn grant_role(env: Env, admin: Signature, nonce: i128, operator: Identifier, role: Role);
+    fn revk_role(env: Env, admin: Signature, nonce: i128, operator: Identifier, role: Role);
+    fn has_role(env: Env, operator: Identifier, role: Role) -> bool;
+    fn token_id(env: Env) -> BytesN<32>;
 }
@@ -1,9 +1,9 @@
-use soroban_sdk::{contracterror};
+use soroban_sdk::contracterror;
+
+// This enums is used to define the errors that can be returned by the contract.
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
-
-// This enums is used to define the errors that can be returned by the contract.
 pub enum Error {
     NotAuthorized = 1,
     NonceShouldBeZero = 2,
@@ -18,4 +18,35 @@ pub enum Error {
     ClientIsNotRenter = 11,
     CarIsNotWithRentedStatus = 12,
     ClientNotAuthorized = 13,
+    InvalidAmount = 14,
+    InvalidDuration = 15,
+    ReservationNotExpired = 16,
+    ReservationExpired = 17,
+    InvalidRole = 18,
+    DropIsNotDenied = 19,
+    CarIsNotInDispute = 20,
+    CarInMaintenance = 21,
+    ClientNotFound = 22,
+    InvalidClientStatus = 23,
+    BookingOverlap = 24,
+    BookingNotExists = 25,
+    NotInBookingWindow = 26,
+    InvalidReading = 27,
+    RentalNotExists = 28,
+    RentalNotCompleted = 29,
+    RentalLimitReached = 30,
+    InsuranceNotExists = 31,
+    SeasonOverlap = 32,
+    SeasonNotExists = 33,
+    InvalidScore = 34,
+    AlreadyRated = 35,
+    InvalidPlate = 36,
+    BranchNotExists = 37,
+    BranchInUse = 38,
+    CarNotAtBranch = 39,
+    MaintOverlap = 40,
+    MaintNotExists = 41,
+    RoleConflict = 42,
+    OutstandingBalance = 43,
+    NothingOwed = 44,
 }
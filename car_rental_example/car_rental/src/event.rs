@@ -0,0 +1,270 @@
+use crate::storage_types::{CarDataKey, Role};
+use soroban_auth::Identifier;
+use soroban_sdk::{symbol, Bytes, BytesN, Env};
+
+pub(crate) fn add_car(e: &Env, plate: Bytes, car_data: CarDataKey) {
+    let topics = (symbol!("add_car"), plate);
+    e.events().publish(topics, car_data);
+}
+
+pub(crate) fn update_car(e: &Env, plate: Bytes, car_data: CarDataKey) {
+    let topics = (symbol!("update_car"), plate);
+    e.events().publish(topics, car_data);
+}
+
+pub(crate) fn set_maint(e: &Env, plate: Bytes, maint: bool) {
+    let topics = (symbol!("set_maint"), plate);
+    e.events().publish(topics, maint);
+}
+
+pub(crate) fn set_late(e: &Env, plate: Bytes, grace: u64, late_rate: i128) {
+    let topics = (symbol!("set_late"), plate);
+    e.events().publish(topics, (grace, late_rate));
+}
+
+pub(crate) fn set_usage(e: &Env, plate: Bytes, km_rate: i128, km_day: u64, fuel_rate: i128) {
+    let topics = (symbol!("set_usage"), plate);
+    e.events().publish(topics, (km_rate, km_day, fuel_rate));
+}
+
+pub(crate) fn remove_car(e: &Env, plate: Bytes) {
+    let topics = (symbol!("remove_car"), plate);
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn migr_plate(e: &Env, plate: Bytes, canonical: Bytes) {
+    let topics = (symbol!("migr_plate"), plate);
+    e.events().publish(topics, canonical);
+}
+
+pub(crate) fn set_branch(e: &Env, branch: Bytes, reloc: i128) {
+    let topics = (symbol!("set_branch"), branch);
+    e.events().publish(topics, reloc);
+}
+
+pub(crate) fn rm_branch(e: &Env, branch: Bytes) {
+    let topics = (symbol!("rm_branch"), branch);
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn reloc_car(e: &Env, plate: Bytes, branch: Bytes) {
+    let topics = (symbol!("reloc_car"), plate);
+    e.events().publish(topics, branch);
+}
+
+pub(crate) fn add_maint(e: &Env, plate: Bytes, start: u64, end: u64) {
+    let topics = (symbol!("add_maint"), plate);
+    e.events().publish(topics, (start, end));
+}
+
+pub(crate) fn rm_maint(e: &Env, plate: Bytes, start: u64) {
+    let topics = (symbol!("rm_maint"), plate);
+    e.events().publish(topics, start);
+}
+
+pub(crate) fn svc_done(e: &Env, plate: Bytes, mileage: u64, cost: i128) {
+    let topics = (symbol!("svc_done"), plate);
+    e.events().publish(topics, (mileage, cost));
+}
+
+pub(crate) fn svc_due(e: &Env, plate: Bytes, rentals: u32, km: u64) {
+    let topics = (symbol!("svc_due"), plate);
+    e.events().publish(topics, (rentals, km));
+}
+
+pub(crate) fn set_svc(e: &Env, rentals: u32, km: u64) {
+    let topics = (symbol!("set_svc"),);
+    e.events().publish(topics, (rentals, km));
+}
+
+pub(crate) fn set_policy(e: &Env, ttl: u64, free: u64, fee: i128) {
+    let topics = (symbol!("set_policy"),);
+    e.events().publish(topics, (ttl, free, fee));
+}
+
+pub(crate) fn open_req(e: &Env, client: Identifier) {
+    let topics = (symbol!("open_req"), client);
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn appr_req(e: &Env, client: Identifier) {
+    let topics = (symbol!("appr_req"), client);
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn deny_req(e: &Env, client: Identifier) {
+    let topics = (symbol!("deny_req"), client);
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn set_valid(e: &Env, ttl: u64) {
+    let topics = (symbol!("set_valid"),);
+    e.events().publish(topics, ttl);
+}
+
+pub(crate) fn suspend(e: &Env, client: Identifier) {
+    let topics = (symbol!("suspend"), client);
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn reinstate(e: &Env, client: Identifier) {
+    let topics = (symbol!("reinstate"), client);
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn revoke(e: &Env, client: Identifier) {
+    let topics = (symbol!("revoke"), client);
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn add_insur(e: &Env, id: u32, premium: i128, deduct: i128) {
+    let topics = (symbol!("add_insur"), id);
+    e.events().publish(topics, (premium, deduct));
+}
+
+pub(crate) fn set_wkend(e: &Env, bps: u32) {
+    let topics = (symbol!("set_wkend"),);
+    e.events().publish(topics, bps);
+}
+
+pub(crate) fn add_season(e: &Env, start: u64, end: u64, bps: u32) {
+    let topics = (symbol!("add_season"), start);
+    e.events().publish(topics, (end, bps));
+}
+
+pub(crate) fn rm_season(e: &Env, start: u64) {
+    let topics = (symbol!("rm_season"), start);
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn set_surge(e: &Env, util: u32, bps: u32) {
+    let topics = (symbol!("set_surge"), util);
+    e.events().publish(topics, bps);
+}
+
+pub(crate) fn set_auto(e: &Env, score: u32, count: u32) {
+    let topics = (symbol!("set_auto"),);
+    e.events().publish(topics, (score, count));
+}
+
+pub(crate) fn set_loyal(e: &Env, rentals: u32, bps: u32) {
+    let topics = (symbol!("set_loyal"), rentals);
+    e.events().publish(topics, bps);
+}
+
+pub(crate) fn set_tier(e: &Env, client: Identifier, tier: u32) {
+    let topics = (symbol!("set_tier"), client);
+    e.events().publish(topics, tier);
+}
+
+pub(crate) fn set_limit(e: &Env, tier: u32, limit: u32) {
+    let topics = (symbol!("set_limit"), tier);
+    e.events().publish(topics, limit);
+}
+
+pub(crate) fn resrve_car(e: &Env, plate: Bytes, client: Identifier, days: u32) {
+    let topics = (symbol!("resrve_car"), plate, client);
+    e.events().publish(topics, days);
+}
+
+pub(crate) fn cancl_resv(e: &Env, plate: Bytes, client: Identifier, refund: i128) {
+    let topics = (symbol!("cancl_resv"), plate, client);
+    e.events().publish(topics, refund);
+}
+
+pub(crate) fn rls_expird(e: &Env, plate: Bytes, client: Identifier, refund: i128) {
+    let topics = (symbol!("rls_expird"), plate, client);
+    e.events().publish(topics, refund);
+}
+
+pub(crate) fn book_car(e: &Env, plate: Bytes, client: Identifier, start: u64, end: u64) {
+    let topics = (symbol!("book_car"), plate, client);
+    e.events().publish(topics, (start, end));
+}
+
+pub(crate) fn cancl_book(e: &Env, plate: Bytes, client: Identifier, refund: i128) {
+    let topics = (symbol!("cancl_book"), plate, client);
+    e.events().publish(topics, refund);
+}
+
+pub(crate) fn take_car(e: &Env, plate: Bytes, client: Identifier, due: u64) {
+    let topics = (symbol!("take_car"), plate, client);
+    e.events().publish(topics, due);
+}
+
+pub(crate) fn drop_car(e: &Env, plate: Bytes, client: Identifier, late_fee: i128) {
+    let topics = (symbol!("drop_car"), plate, client);
+    e.events().publish(topics, late_fee);
+}
+
+pub(crate) fn accpt_drop(e: &Env, plate: Bytes, client: Identifier, refund: i128) {
+    let topics = (symbol!("accpt_drop"), plate, client);
+    e.events().publish(topics, refund);
+}
+
+pub(crate) fn deny_drop(
+    e: &Env,
+    plate: Bytes,
+    client: Identifier,
+    reason: u32,
+    cost: i128,
+    evidence: BytesN<32>,
+) {
+    let topics = (symbol!("deny_drop"), plate, client);
+    e.events().publish(topics, (reason, cost, evidence));
+}
+
+pub(crate) fn accpt_dmg(e: &Env, plate: Bytes, client: Identifier, cost: i128) {
+    let topics = (symbol!("accpt_dmg"), plate, client);
+    e.events().publish(topics, cost);
+}
+
+pub(crate) fn dispute(e: &Env, plate: Bytes, client: Identifier, cost: i128) {
+    let topics = (symbol!("dispute"), plate, client);
+    e.events().publish(topics, cost);
+}
+
+pub(crate) fn claim(e: &Env, plate: Bytes, client: Identifier, covered: i128) {
+    let topics = (symbol!("claim"), plate, client);
+    e.events().publish(topics, covered);
+}
+
+pub(crate) fn score_ret(e: &Env, client: Identifier, id: u32, score: u32) {
+    let topics = (symbol!("score_ret"), client);
+    e.events().publish(topics, (id, score));
+}
+
+pub(crate) fn rate_rent(e: &Env, plate: Bytes, client: Identifier, car: u32, service: u32) {
+    let topics = (symbol!("rate_rent"), plate, client);
+    e.events().publish(topics, (car, service));
+}
+
+pub(crate) fn settle(e: &Env, plate: Bytes, client: Identifier, amount: i128) {
+    let topics = (symbol!("settle"), plate, client);
+    e.events().publish(topics, amount);
+}
+
+pub(crate) fn owed(e: &Env, client: Identifier, owed: i128) {
+    let topics = (symbol!("owed"), client);
+    e.events().publish(topics, owed);
+}
+
+pub(crate) fn pay_owed(e: &Env, client: Identifier, amount: i128) {
+    let topics = (symbol!("pay_owed"), client);
+    e.events().publish(topics, amount);
+}
+
+pub(crate) fn set_admin(e: &Env, admin: Identifier, new_admin: Identifier) {
+    let topics = (symbol!("set_admin"), admin);
+    e.events().publish(topics, new_admin);
+}
+
+pub(crate) fn grant_role(e: &Env, operator: Identifier, role: Role) {
+    let topics = (symbol!("grant_role"), operator);
+    e.events().publish(topics, role);
+}
+
+pub(crate) fn revk_role(e: &Env, operator: Identifier, role: Role) {
+    let topics = (symbol!("revk_role"), operator);
+    e.events().publish(topics, role);
+}
@@ -1,12 +1,16 @@
 #![no_std]
+// the contract functions take the signature, the nonce and all the arguments of the call
+#![allow(clippy::too_many_arguments)]
 
 // All the imports necessary to execute the mods from the contract
 mod contract;
 mod contract_trait;
 mod errors;
+mod event;
 mod metadata;
 mod storage_types;
 #[cfg(test)]
 mod test;
 pub mod testutils;
 pub use crate::contract::CarRentalContract;
+pub use crate::contract_trait::CarRentalTrait;
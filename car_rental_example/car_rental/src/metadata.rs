@@ -1,16 +1,99 @@
 use crate::errors::Error;
-use crate::storage_types::{CarDataKey, ClientStatus, DataKey, RentedCarDataKey};
+use crate::storage_types::{
+    AutoAppr, Booking, CarDataKey, ClientStatus, DataKey, Insurance, Loyalty, MaintWin, PendReq,
+    Rating, RentalRec, RentedCarDataKey, Reputation, ResvPolicy, Role, Season, ServiceRec, Surge,
+    SvcRule, SvcState,
+};
 use soroban_auth::{Identifier, Signature};
-use soroban_sdk::{panic_with_error, BigInt, Bytes, Env};
+use soroban_sdk::{panic_with_error, Bytes, BytesN, Env, Vec};
 
 // This file contains all the functions from the contract to access and write in storage.
 
 pub fn write_client(env: &Env, client: Identifier, status: ClientStatus) {
-    env.data().set(DataKey::Client(client), status)
+    // keep the pending queue updated with the decided requests
+    if status != ClientStatus::Pending {
+        remove_pending(env, &client);
+    }
+    env.storage().set(DataKey::Client(client), status)
 }
 
+// a client that never opened a request is Unknown, and an approval after its expiration is Expired
 pub fn read_client(env: &Env, client: Identifier) -> ClientStatus {
-    env.data().get_unchecked(DataKey::Client(client)).unwrap()
+    let status = env
+        .storage()
+        .get(DataKey::Client(client.clone()))
+        .unwrap_or(Ok(ClientStatus::Unknown))
+        .unwrap();
+    if status == ClientStatus::Approved {
+        let expires = read_approval_expiry(env, &client);
+        if expires != 0 && env.ledger().timestamp() >= expires {
+            return ClientStatus::Expired;
+        }
+    }
+    status
+}
+
+// approval validity period in seconds (0 = the approvals don't expire)
+pub fn read_approval_ttl(env: &Env) -> u64 {
+    env.storage()
+        .get(DataKey::ApprTtl)
+        .unwrap_or(Ok(0))
+        .unwrap()
+}
+
+pub fn write_approval_ttl(env: &Env, ttl: u64) {
+    env.storage().set(DataKey::ApprTtl, ttl)
+}
+
+// approval expiration timestamp of the client (0 = no expiration)
+pub fn read_approval_expiry(env: &Env, client: &Identifier) -> u64 {
+    env.storage()
+        .get(DataKey::ApprExp(client.clone()))
+        .unwrap_or(Ok(0))
+        .unwrap()
+}
+
+pub fn write_approval_expiry(env: &Env, client: &Identifier, expires: u64) {
+    if expires == 0 {
+        env.storage().remove(DataKey::ApprExp(client.clone()))
+    } else {
+        env.storage().set(DataKey::ApprExp(client.clone()), expires)
+    }
+}
+
+// client requests waiting for approval, from the oldest
+pub fn read_pending(env: &Env) -> Vec<PendReq> {
+    env.storage()
+        .get(DataKey::Pending)
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
+}
+
+fn write_pending(env: &Env, requests: Vec<PendReq>) {
+    if requests.is_empty() {
+        env.storage().remove(DataKey::Pending)
+    } else {
+        env.storage().set(DataKey::Pending, requests)
+    }
+}
+
+// add a request to the end of the queue, replacing the previous request of the client
+pub fn add_pending(env: &Env, request: PendReq) {
+    remove_pending(env, &request.client);
+    let mut requests = read_pending(env);
+    requests.push_back(request);
+    write_pending(env, requests)
+}
+
+fn remove_pending(env: &Env, client: &Identifier) {
+    let mut requests = read_pending(env);
+    for (i, request) in requests.iter().enumerate() {
+        if request.unwrap().client == *client {
+            requests.remove(i as u32);
+            write_pending(env, requests);
+            return;
+        }
+    }
 }
 
 pub fn is_client_authorized(env: &Env, client: &Identifier) -> bool {
@@ -18,87 +101,611 @@ pub fn is_client_authorized(env: &Env, client: &Identifier) -> bool {
 }
 
 pub fn write_car(env: &Env, plate: &Bytes, car_data: CarDataKey) {
-    env.data().set(DataKey::Car(plate.clone()), car_data)
+    // keep the plate index updated with the new cars
+    if !has_car(env, plate) {
+        let mut plates = read_plates(env);
+        plates.push_back(plate.clone());
+        env.storage().set(DataKey::Plates, plates)
+    }
+    env.storage().set(DataKey::Car(plate.clone()), car_data)
 }
 
 pub fn read_car(env: &Env, plate: Bytes) -> CarDataKey {
-    env.data().get_unchecked(DataKey::Car(plate)).unwrap()
+    match env.storage().get(DataKey::Car(plate)) {
+        Some(car_data) => car_data.unwrap(),
+        None => panic_with_error!(env, Error::CarNotExists),
+    }
 }
 
 pub fn has_car(env: &Env, plate: &Bytes) -> bool {
-    env.data().has(DataKey::Car(plate.clone()))
+    env.storage().has(DataKey::Car(plate.clone()))
 }
 
 pub fn remove_car(env: &Env, plate: &Bytes) {
-    env.data().remove(DataKey::Car(plate.clone()))
+    let mut plates = read_plates(env);
+    if let Some(index) = plates.first_index_of(plate) {
+        plates.remove(index);
+        env.storage().set(DataKey::Plates, plates)
+    }
+    env.storage().remove(DataKey::Car(plate.clone()));
+    // the service history is kept like the rental history
+    env.storage().remove(DataKey::MaintWins(plate.clone()));
+    env.storage().remove(DataKey::SvcState(plate.clone()))
+}
+
+// move all the entries of the car stored with the plate from to the plate to,
+// keeping the position of the car in the plate index and in the active plates of the renter.
+// A car missing from the plate index is added to its end
+pub fn move_car(env: &Env, from: &Bytes, to: &Bytes) {
+    let mut plates = read_plates(env);
+    match plates.first_index_of(from) {
+        Some(index) => plates.set(index, to.clone()),
+        None => plates.push_back(to.clone()),
+    }
+    env.storage().set(DataKey::Plates, plates);
+    env.storage()
+        .set(DataKey::Car(to.clone()), read_car(env, from.clone()));
+    env.storage().remove(DataKey::Car(from.clone()));
+
+    if has_rented_car(env, from) {
+        let rented_car_data = read_rented_car(env, from);
+        let mut plates = read_active(env, &rented_car_data.renter);
+        if let Some(index) = plates.first_index_of(from) {
+            plates.set(index, to.clone());
+            write_active(env, &rented_car_data.renter, plates)
+        }
+        env.storage()
+            .set(DataKey::RentedCar(to.clone()), rented_car_data);
+        env.storage().remove(DataKey::RentedCar(from.clone()))
+    }
+
+    write_bookings(env, to, read_bookings(env, from));
+    write_bookings(env, from, Vec::new(env));
+
+    let car_history = read_car_history(env, from);
+    if !car_history.is_empty() {
+        for id in car_history.iter() {
+            let mut rental = read_rental(env, id.unwrap());
+            rental.plate = to.clone();
+            write_rental(env, &rental);
+        }
+        env.storage().set(DataKey::CarHist(to.clone()), car_history);
+        env.storage().remove(DataKey::CarHist(from.clone()))
+    }
+
+    if env.storage().has(DataKey::CarRating(from.clone())) {
+        write_car_rating(env, to, read_car_rating(env, from));
+        env.storage().remove(DataKey::CarRating(from.clone()))
+    }
+
+    write_maint_wins(env, to, read_maint_wins(env, from));
+    write_maint_wins(env, from, Vec::new(env));
+
+    let services = read_services(env, from);
+    if !services.is_empty() {
+        env.storage().set(DataKey::Services(to.clone()), services);
+        env.storage().remove(DataKey::Services(from.clone()))
+    }
+
+    write_svc_state(env, to, read_svc_state(env, from));
+    env.storage().remove(DataKey::SvcState(from.clone()))
+}
+
+// plates of all the cars in the contract, in the order they were added
+pub fn read_plates(env: &Env) -> Vec<Bytes> {
+    env.storage()
+        .get(DataKey::Plates)
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
+}
+
+// codes of the branches, in the order they were added
+pub fn read_branches(env: &Env) -> Vec<Bytes> {
+    env.storage()
+        .get(DataKey::Branches)
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
+}
+
+pub fn has_branch(env: &Env, branch: &Bytes) -> bool {
+    env.storage().has(DataKey::Branch(branch.clone()))
+}
+
+// relocation fee of the one-way rentals returned at the branch
+pub fn read_branch(env: &Env, branch: &Bytes) -> i128 {
+    match env.storage().get(DataKey::Branch(branch.clone())) {
+        Some(reloc) => reloc.unwrap(),
+        None => panic_with_error!(env, Error::BranchNotExists),
+    }
+}
+
+pub fn write_branch(env: &Env, branch: &Bytes, reloc: i128) {
+    // keep the branch index updated with the new branches
+    if !has_branch(env, branch) {
+        let mut branches = read_branches(env);
+        branches.push_back(branch.clone());
+        env.storage().set(DataKey::Branches, branches)
+    }
+    env.storage().set(DataKey::Branch(branch.clone()), reloc)
+}
+
+pub fn remove_branch(env: &Env, branch: &Bytes) {
+    let mut branches = read_branches(env);
+    if let Some(index) = branches.first_index_of(branch) {
+        branches.remove(index);
+        env.storage().set(DataKey::Branches, branches)
+    }
+    env.storage().remove(DataKey::Branch(branch.clone()))
 }
 
 pub fn write_rented_car(env: &Env, plate: &Bytes, rented_car_data: RentedCarDataKey) {
-    env.data()
+    // keep the active plates of the client updated with the new rentals
+    if !has_rented_car(env, plate) {
+        let mut plates = read_active(env, &rented_car_data.renter);
+        plates.push_back(plate.clone());
+        write_active(env, &rented_car_data.renter, plates)
+    }
+    env.storage()
         .set(DataKey::RentedCar(plate.clone()), rented_car_data)
 }
 
 pub fn read_rented_car(env: &Env, plate: &Bytes) -> RentedCarDataKey {
-    env.data()
-        .get_unchecked(DataKey::RentedCar(plate.clone()))
-        .unwrap()
+    match env.storage().get(DataKey::RentedCar(plate.clone())) {
+        Some(rented_car_data) => rented_car_data.unwrap(),
+        None => panic_with_error!(env, Error::CarIsNotRented),
+    }
 }
 
 pub fn remove_rented_car(env: &Env, plate: &Bytes) {
-    env.data().remove(DataKey::RentedCar(plate.clone()))
+    let renter = read_rented_car(env, plate).renter;
+    let mut plates = read_active(env, &renter);
+    if let Some(index) = plates.first_index_of(plate) {
+        plates.remove(index);
+        write_active(env, &renter, plates)
+    }
+    env.storage().remove(DataKey::RentedCar(plate.clone()))
+}
+
+// plates reserved or rented by the client, in the order they were reserved
+pub fn read_active(env: &Env, client: &Identifier) -> Vec<Bytes> {
+    env.storage()
+        .get(DataKey::Active(client.clone()))
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
+}
+
+fn write_active(env: &Env, client: &Identifier, plates: Vec<Bytes>) {
+    if plates.is_empty() {
+        env.storage().remove(DataKey::Active(client.clone()))
+    } else {
+        env.storage().set(DataKey::Active(client.clone()), plates)
+    }
+}
+
+// clients are in the tier 0 until the admin sets another one
+pub fn read_tier(env: &Env, client: &Identifier) -> u32 {
+    env.storage()
+        .get(DataKey::Tier(client.clone()))
+        .unwrap_or(Ok(0))
+        .unwrap()
+}
+
+pub fn write_tier(env: &Env, client: &Identifier, tier: u32) {
+    env.storage().set(DataKey::Tier(client.clone()), tier)
+}
+
+// a tier without limit accepts any number of active rentals
+pub fn read_tier_limit(env: &Env, tier: u32) -> u32 {
+    env.storage()
+        .get(DataKey::TierLimit(tier))
+        .unwrap_or(Ok(u32::MAX))
+        .unwrap()
+}
+
+pub fn write_tier_limit(env: &Env, tier: u32, limit: u32) {
+    env.storage().set(DataKey::TierLimit(tier), limit)
 }
 
 pub fn has_rented_car(env: &Env, plate: &Bytes) -> bool {
-    env.data().has(DataKey::RentedCar(plate.clone()))
+    env.storage().has(DataKey::RentedCar(plate.clone()))
+}
+
+// bookings of the car, sorted by the start of the period
+pub fn read_bookings(env: &Env, plate: &Bytes) -> Vec<Booking> {
+    env.storage()
+        .get(DataKey::Bookings(plate.clone()))
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
+}
+
+pub fn write_bookings(env: &Env, plate: &Bytes, bookings: Vec<Booking>) {
+    // keep the booked plates of the clients updated with the new and the removed bookings
+    let old = read_bookings(env, plate);
+    for booking in old.iter() {
+        let renter = booking.unwrap().renter;
+        if !has_renter(&bookings, &renter) {
+            let mut plates = read_booked(env, &renter);
+            if let Some(index) = plates.first_index_of(plate) {
+                plates.remove(index);
+                write_booked(env, &renter, plates)
+            }
+        }
+    }
+    for booking in bookings.iter() {
+        let renter = booking.unwrap().renter;
+        if !has_renter(&old, &renter) {
+            let mut plates = read_booked(env, &renter);
+            if !plates.contains(plate) {
+                plates.push_back(plate.clone());
+                write_booked(env, &renter, plates)
+            }
+        }
+    }
+    if bookings.is_empty() {
+        env.storage().remove(DataKey::Bookings(plate.clone()))
+    } else {
+        env.storage()
+            .set(DataKey::Bookings(plate.clone()), bookings)
+    }
+}
+
+fn has_renter(bookings: &Vec<Booking>, renter: &Identifier) -> bool {
+    bookings
+        .iter()
+        .any(|booking| booking.unwrap().renter == *renter)
+}
+
+// plates with bookings of the client, in the order they were booked
+pub fn read_booked(env: &Env, client: &Identifier) -> Vec<Bytes> {
+    env.storage()
+        .get(DataKey::Booked(client.clone()))
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
+}
+
+fn write_booked(env: &Env, client: &Identifier, plates: Vec<Bytes>) {
+    if plates.is_empty() {
+        env.storage().remove(DataKey::Booked(client.clone()))
+    } else {
+        env.storage().set(DataKey::Booked(client.clone()), plates)
+    }
+}
+
+// add a closed rental to the history of the car and of the client
+pub fn add_rental(env: &Env, mut rental: RentalRec) {
+    let id: u32 = env
+        .storage()
+        .get(DataKey::RentalCnt)
+        .unwrap_or(Ok(0))
+        .unwrap();
+    rental.id = id;
+
+    let mut car_history = read_car_history(env, &rental.plate);
+    car_history.push_back(id);
+    env.storage()
+        .set(DataKey::CarHist(rental.plate.clone()), car_history);
+
+    let mut client_history = read_client_history(env, &rental.renter);
+    client_history.push_back(id);
+    env.storage()
+        .set(DataKey::ClntHist(rental.renter.clone()), client_history);
+
+    env.storage().set(DataKey::Rental(id), rental);
+    env.storage().set(DataKey::RentalCnt, id + 1)
+}
+
+pub fn write_rental(env: &Env, rental: &RentalRec) {
+    env.storage().set(DataKey::Rental(rental.id), rental)
+}
+
+pub fn read_rental(env: &Env, id: u32) -> RentalRec {
+    match env.storage().get(DataKey::Rental(id)) {
+        Some(rental) => rental.unwrap(),
+        None => panic_with_error!(env, Error::RentalNotExists),
+    }
+}
+
+// ids of the rentals of the car, from the oldest
+pub fn read_car_history(env: &Env, plate: &Bytes) -> Vec<u32> {
+    env.storage()
+        .get(DataKey::CarHist(plate.clone()))
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
+}
+
+// ids of the rentals of the client, from the oldest
+pub fn read_client_history(env: &Env, client: &Identifier) -> Vec<u32> {
+    env.storage()
+        .get(DataKey::ClntHist(client.clone()))
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
 }
 
 pub fn write_admin(env: &Env, admin: Identifier) {
-    env.data().set(DataKey::Admin, admin)
+    env.storage().set(DataKey::Admin, admin)
 }
 
 pub fn has_admin(env: &Env) -> bool {
-    env.data().has(DataKey::Admin)
+    env.storage().has(DataKey::Admin)
 }
 
 pub fn read_admin(env: &Env) -> Identifier {
-    env.data().get_unchecked(DataKey::Admin).unwrap()
+    env.storage().get_unchecked(DataKey::Admin).unwrap()
 }
 
-pub fn check_admin(env: &Env, auth: &Signature) {
-    let auth_id = auth.identifier(&env);
-    if auth_id != read_admin(&env) {
+pub fn read_roles(env: &Env, id: &Identifier) -> u32 {
+    let key = DataKey::Roles(id.clone());
+    env.storage().get(key).unwrap_or(Ok(0)).unwrap()
+}
+
+pub fn write_roles(env: &Env, id: &Identifier, roles: u32) {
+    let key = DataKey::Roles(id.clone());
+    if roles == 0 {
+        env.storage().remove(key)
+    } else {
+        env.storage().set(key, roles)
+    }
+}
+
+// the owner (admin) has all the roles
+// the owner has all the roles but Arbiter: the disputes against its inspection are settled by another operator
+pub fn has_role(env: &Env, id: &Identifier, role: Role) -> bool {
+    if *id == read_admin(env) {
+        return role != Role::Arbiter;
+    }
+    read_roles(env, id) & (role as u32) != 0
+}
+
+pub fn check_role(env: &Env, auth: &Signature, role: Role) {
+    let auth_id = auth.identifier(env);
+    if !has_role(env, &auth_id, role) {
         panic_with_error!(&env, Error::NotAuthorized)
     }
 }
 
-pub fn read_nonce(env: &Env, id: &Identifier) -> BigInt {
-    let key = DataKey::Nonce(id.clone());
-    env.data()
-        .get(key)
-        .unwrap_or_else(|| Ok(BigInt::zero(env)))
+pub fn write_token(env: &Env, token_id: BytesN<32>) {
+    env.storage().set(DataKey::Token, token_id)
+}
+
+pub fn read_token(env: &Env) -> BytesN<32> {
+    env.storage().get_unchecked(DataKey::Token).unwrap()
+}
+
+pub fn write_policy(env: &Env, policy: ResvPolicy) {
+    env.storage().set(DataKey::Policy, policy)
+}
+
+pub fn read_policy(env: &Env) -> ResvPolicy {
+    env.storage().get_unchecked(DataKey::Policy).unwrap()
+}
+
+// store a new insurance product and return its id (the ids start at 1, 0 is no insurance)
+pub fn add_insurance(env: &Env, insurance: Insurance) -> u32 {
+    let id: u32 = env
+        .storage()
+        .get(DataKey::InsurCnt)
+        .unwrap_or(Ok(0))
+        .unwrap()
+        + 1;
+    env.storage().set(DataKey::Insurance(id), insurance);
+    env.storage().set(DataKey::InsurCnt, id);
+    id
+}
+
+pub fn read_insurance(env: &Env, id: u32) -> Insurance {
+    match env.storage().get(DataKey::Insurance(id)) {
+        Some(insurance) => insurance.unwrap(),
+        None => panic_with_error!(env, Error::InsuranceNotExists),
+    }
+}
+
+pub fn read_pool(env: &Env) -> i128 {
+    env.storage().get(DataKey::Pool).unwrap_or(Ok(0)).unwrap()
+}
+
+pub fn write_pool(env: &Env, amount: i128) {
+    env.storage().set(DataKey::Pool, amount)
+}
+
+// charges of the previous rentals not paid by the client
+pub fn read_owed(env: &Env, client: &Identifier) -> i128 {
+    env.storage()
+        .get(DataKey::Owed(client.clone()))
+        .unwrap_or(Ok(0))
+        .unwrap()
+}
+
+pub fn write_owed(env: &Env, client: &Identifier, amount: i128) {
+    if amount == 0 {
+        env.storage().remove(DataKey::Owed(client.clone()))
+    } else {
+        env.storage().set(DataKey::Owed(client.clone()), amount)
+    }
+}
+
+// multiplier of the daily rate on weekends, in basis points (10000 = 1x)
+pub fn read_weekend(env: &Env) -> u32 {
+    env.storage()
+        .get(DataKey::Weekend)
+        .unwrap_or(Ok(10000))
+        .unwrap()
+}
+
+pub fn write_weekend(env: &Env, bps: u32) {
+    env.storage().set(DataKey::Weekend, bps)
+}
+
+pub fn read_maint_wins(env: &Env, plate: &Bytes) -> Vec<MaintWin> {
+    env.storage()
+        .get(DataKey::MaintWins(plate.clone()))
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
+}
+
+pub fn write_maint_wins(env: &Env, plate: &Bytes, windows: Vec<MaintWin>) {
+    if windows.is_empty() {
+        env.storage().remove(DataKey::MaintWins(plate.clone()))
+    } else {
+        env.storage()
+            .set(DataKey::MaintWins(plate.clone()), windows)
+    }
+}
+
+// completed services of the car, from the oldest
+pub fn read_services(env: &Env, plate: &Bytes) -> Vec<ServiceRec> {
+    env.storage()
+        .get(DataKey::Services(plate.clone()))
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
+}
+
+pub fn add_service(env: &Env, plate: &Bytes, service: ServiceRec) {
+    let mut services = read_services(env, plate);
+    services.push_back(service);
+    env.storage()
+        .set(DataKey::Services(plate.clone()), services)
+}
+
+// usage of the car since its last service (nothing before the first rental)
+pub fn read_svc_state(env: &Env, plate: &Bytes) -> SvcState {
+    env.storage()
+        .get(DataKey::SvcState(plate.clone()))
+        .unwrap_or(Ok(SvcState {
+            rentals: 0,
+            km: 0,
+            due: false,
+        }))
         .unwrap()
 }
 
+pub fn write_svc_state(env: &Env, plate: &Bytes, state: SvcState) {
+    if state.rentals == 0 && state.km == 0 {
+        env.storage().remove(DataKey::SvcState(plate.clone()))
+    } else {
+        env.storage().set(DataKey::SvcState(plate.clone()), state)
+    }
+}
+
+// no car is due for service until the admin sets the rule
+pub fn read_svc_rule(env: &Env) -> SvcRule {
+    env.storage()
+        .get(DataKey::SvcRule)
+        .unwrap_or(Ok(SvcRule { rentals: 0, km: 0 }))
+        .unwrap()
+}
 
+pub fn write_svc_rule(env: &Env, rule: SvcRule) {
+    env.storage().set(DataKey::SvcRule, rule)
+}
+
+pub fn read_seasons(env: &Env) -> Vec<Season> {
+    env.storage()
+        .get(DataKey::Seasons)
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
+}
+
+pub fn write_seasons(env: &Env, seasons: Vec<Season>) {
+    if seasons.is_empty() {
+        env.storage().remove(DataKey::Seasons)
+    } else {
+        env.storage().set(DataKey::Seasons, seasons)
+    }
+}
+
+pub fn read_surges(env: &Env) -> Vec<Surge> {
+    env.storage()
+        .get(DataKey::Surges)
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
+}
+
+pub fn write_surges(env: &Env, surges: Vec<Surge>) {
+    if surges.is_empty() {
+        env.storage().remove(DataKey::Surges)
+    } else {
+        env.storage().set(DataKey::Surges, surges)
+    }
+}
+
+pub fn read_reputation(env: &Env, client: &Identifier) -> Reputation {
+    env.storage()
+        .get(DataKey::ClntRep(client.clone()))
+        .unwrap_or(Ok(Reputation {
+            rentals: 0,
+            scores: 0,
+            total: 0,
+        }))
+        .unwrap()
+}
+
+pub fn write_reputation(env: &Env, client: &Identifier, reputation: Reputation) {
+    env.storage()
+        .set(DataKey::ClntRep(client.clone()), reputation)
+}
+
+pub fn read_car_rating(env: &Env, plate: &Bytes) -> Rating {
+    env.storage()
+        .get(DataKey::CarRating(plate.clone()))
+        .unwrap_or(Ok(Rating {
+            count: 0,
+            car: 0,
+            service: 0,
+        }))
+        .unwrap()
+}
+
+pub fn write_car_rating(env: &Env, plate: &Bytes, rating: Rating) {
+    env.storage().set(DataKey::CarRating(plate.clone()), rating)
+}
+
+// the auto approval is disabled until the admin sets it
+pub fn read_auto_approval(env: &Env) -> AutoAppr {
+    env.storage()
+        .get(DataKey::AutoAppr)
+        .unwrap_or(Ok(AutoAppr { score: 0, count: 0 }))
+        .unwrap()
+}
+
+pub fn write_auto_approval(env: &Env, auto_approval: AutoAppr) {
+    env.storage().set(DataKey::AutoAppr, auto_approval)
+}
+
+pub fn read_loyalty(env: &Env) -> Vec<Loyalty> {
+    env.storage()
+        .get(DataKey::Loyalty)
+        .unwrap_or(Ok(Vec::new(env)))
+        .unwrap()
+}
+
+pub fn write_loyalty(env: &Env, loyalty: Vec<Loyalty>) {
+    if loyalty.is_empty() {
+        env.storage().remove(DataKey::Loyalty)
+    } else {
+        env.storage().set(DataKey::Loyalty, loyalty)
+    }
+}
+
+pub fn read_nonce(env: &Env, id: &Identifier) -> i128 {
+    let key = DataKey::Nonce(id.clone());
+    env.storage().get(key).unwrap_or(Ok(0)).unwrap()
+}
 
 // verify if the nonce is as expected by the contract and increment it
-pub fn verify_and_consume_nonce(env: &Env, auth: &Signature, expected_nonce: &BigInt) {
-    match auth {
-        Signature::Invoker => {
-            if BigInt::zero(&env) != expected_nonce {
-                panic_with_error!(&env, Error::NonceShouldBeZero)
-            }
-            return;
+pub fn verify_and_consume_nonce(env: &Env, auth: &Signature, expected_nonce: &i128) {
+    if auth == &Signature::Invoker {
+        if *expected_nonce != 0 {
+            panic_with_error!(&env, Error::NonceShouldBeZero)
         }
-        _ => {}
+        return;
     }
 
-    let id = auth.identifier(&env);
+    let id = auth.identifier(env);
     let key = DataKey::Nonce(id.clone());
     let nonce = read_nonce(env, &id);
 
-    if nonce != expected_nonce {
+    if nonce != *expected_nonce {
         panic_with_error!(&env, Error::IncorrectNonce)
     }
-    env.data().set(key, &nonce + 1);
+    env.storage().set(key, nonce + 1);
 }
@@ -1,6 +1,6 @@
-use soroban_sdk::{contracttype};
-use soroban_auth::{Identifier};
-use soroban_sdk::{Bytes};
+use soroban_auth::Identifier;
+use soroban_sdk::contracttype;
+use soroban_sdk::{Bytes, BytesN, Vec};
 
 // This file contains all the storage variable types.
 #[contracttype]
@@ -9,6 +9,34 @@ pub struct CarDataKey {
     pub model: Bytes,
     pub color: Bytes,
     pub horse: i32,
+    pub year: u32,
+    pub mileage: u64,    // odometer (km)
+    pub category: Bytes, // e.g. economy, suv, van
+    pub location: Bytes, // current branch of the car
+    pub home: Bytes,     // home branch of the car
+    pub daily_rate: i128,
+    pub deposit: i128,
+    pub grace: u64,      // seconds after the agreed return time without late fee
+    pub late_rate: i128, // late fee per hour
+    pub maint: bool,     // in maintenance, can't be reserved
+    pub km_rate: i128,   // fee per km above the mileage allowance
+    pub km_day: u64,     // mileage allowance per rental day (km)
+    pub fuel_rate: i128, // fee per fuel level point (%) below the pickup level
+}
+
+// Data informed by the admin to add or update a car.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CarSpec {
+    pub model: Bytes,
+    pub color: Bytes,
+    pub horse: i32,
+    pub year: u32,
+    pub mileage: u64,
+    pub category: Bytes,
+    pub home: Bytes,
+    pub daily_rate: i128,
+    pub deposit: i128,
 }
 
 #[contracttype]
@@ -16,8 +44,263 @@ pub struct CarDataKey {
 pub struct RentedCarDataKey {
     pub renter: Identifier,
     pub status: RentedCarStatus,
+    pub days: u32,
+    pub deposit: i128,        // amount of the deposit still in escrow
+    pub reason: u32,          // reason code of the last denied drop
+    pub reserved: u64,        // reservation timestamp
+    pub expires: u64,         // the reservation expires if the car is not taken until then
+    pub picked_up: u64,       // pickup timestamp (0 while reserved)
+    pub due: u64,             // agreed return timestamp (0 while reserved)
+    pub returned: u64,        // drop timestamp (0 while not dropped)
+    pub late_fee: i128,       // late fee computed at the return
+    pub denials: Vec<Denial>, // denied drops, from the oldest
+    pub withheld: i128,       // amount of the deposit withheld (damages and cancellation fee)
+    pub dmg_cost: i128,       // estimated cost of the pending damage report
+    pub evidence: BytesN<32>, // hash of the off-chain evidence of the last damage report
+    pub price: i128,          // rental price quoted at the reservation, charged at the pickup
+    pub odo_out: u64,         // odometer at the pickup (km)
+    pub fuel_out: u32,        // fuel level at the pickup (%)
+    pub odo_in: u64,          // odometer at the return, confirmed at the drop acceptance
+    pub fuel_in: u32,         // fuel level at the return, confirmed at the drop acceptance
+    pub km_fee: i128,         // mileage fee computed at the drop acceptance
+    pub fuel_fee: i128,       // fuel surcharge computed at the drop acceptance
+    pub insurance: u32,       // insurance product bought at the reservation (0 without insurance)
+    pub premium: i128,        // premium paid to the claims pool
+    pub deduct: i128,         // deductible of the insurance, the pool pays the damages above it
+    pub covered: i128,        // damages paid by the claims pool
+    pub pickup: Bytes,        // branch where the car is taken
+    pub dropoff: Bytes,       // branch where the car is returned
+    pub reloc: i128,          // relocation fee of a one-way rental, charged at the pickup
+}
+
+// Closed rental kept in the history of the car and of the client
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RentalRec {
+    pub id: u32,
+    pub plate: Bytes,
+    pub renter: Identifier,
+    pub outcome: Outcome,
+    pub days: u32,
+    pub reserved: u64,
+    pub picked_up: u64,
+    pub due: u64,
+    pub returned: u64,
+    pub closed: u64,          // timestamp the rental was closed
+    pub denials: Vec<Denial>, // denied drops, from the oldest
+    pub withheld: i128,
+    pub late_fee: i128,
+    pub price: i128,
+    pub km: u64, // driven km
+    pub km_fee: i128,
+    pub fuel_fee: i128,
+    pub refunded: i128, // deposit refunded to the client
+    pub insurance: u32,
+    pub premium: i128,
+    pub covered: i128, // damages paid by the claims pool
+    pub score: u32,    // return score given by the inspector (1 to 5, 0 while not scored)
+    pub car_rate: u32, // car rating given by the client (1 to 5, 0 while not rated)
+    pub svc_rate: u32, // service rating given by the client (1 to 5, 0 while not rated)
+    pub pickup: Bytes,
+    pub dropoff: Bytes,
+    pub reloc: i128, // relocation fee of a one-way rental
+}
+
+// Damage report of a denied drop
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Denial {
+    pub reason: u32,
+    pub cost: i128,           // estimated cost of the damage
+    pub evidence: BytesN<32>, // hash of the off-chain evidence
+    pub denied: u64,          // timestamp of the denial
+}
+
+// Final invoice of a completed rental
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Invoice {
+    pub id: u32,
+    pub plate: Bytes,
+    pub renter: Identifier,
+    pub days: u32,
+    pub price: i128, // rental (daily rate * days)
+    pub late_fee: i128,
+    pub km: u64,        // driven km
+    pub km_fee: i128,   // km above the allowance * km rate
+    pub fuel_fee: i128, // missing fuel level * fuel rate
+    pub premium: i128,  // insurance premium
+    pub damage: i128,   // withheld from the deposit for damages
+    pub covered: i128,  // damages paid by the claims pool
+    pub reloc: i128,    // relocation fee of a one-way rental
+    pub total: i128,    // price + late fee + km fee + fuel fee + premium + damage + reloc
+    pub refunded: i128, // deposit refunded to the client
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResvPolicy {
+    pub ttl: u64,  // seconds a reservation is kept without taking the car
+    pub free: u64, // seconds after the reservation that the client can cancel without fee
+    pub fee: i128, // cancellation fee withheld from the deposit
+}
+
+// Insurance product bought at the reservation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Insurance {
+    pub premium: i128, // paid by the client to the claims pool at the reservation
+    pub deduct: i128,  // damages up to it are withheld from the deposit
+}
+
+// Future reservation of a car for the [start, end) period
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Booking {
+    pub renter: Identifier,
+    pub start: u64,     // start of the period, the car can be taken from it
+    pub end: u64,       // end of the period, the car must be returned until it
+    pub deposit: i128,  // deposit in escrow
+    pub booked: u64,    // booking timestamp
+    pub price: i128,    // rental price quoted at the booking, charged at the pickup
+    pub pickup: Bytes,  // branch where the car is taken
+    pub dropoff: Bytes, // branch where the car is returned
+    pub reloc: i128,    // relocation fee of a one-way rental, charged at the pickup
+    pub insurance: u32, // insurance product bought at the booking (0 without insurance)
+    pub premium: i128,  // premium paid to the claims pool
+    pub deduct: i128,   // deductible of the insurance
+}
+
+// Period [start, end) when the car is out of service for a scheduled maintenance
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MaintWin {
+    pub start: u64,
+    pub end: u64,
+}
+
+// Completed service of a car
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServiceRec {
+    pub done: u64,    // service timestamp
+    pub mileage: u64, // odometer (km) at the service
+    pub cost: i128,   // cost of the service
+    pub rentals: u32, // completed rentals since the previous service
+    pub km: u64,      // km driven in the rentals since the previous service
 }
 
+// A car is due for service after rentals completed rentals or km driven since its last service
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SvcRule {
+    pub rentals: u32, // 0 = no limit
+    pub km: u64,      // 0 = no limit
+}
+
+// Usage of a car since its last service
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SvcState {
+    pub rentals: u32, // completed rentals
+    pub km: u64,      // km driven in the rentals
+    pub due: bool,    // the service rule is reached
+}
+
+// Multiplier (basis points, 10000 = 1x) of the daily rate
+// for the days starting in the [start, end) period
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Season {
+    pub start: u64,
+    pub end: u64,
+    pub bps: u32,
+}
+
+// Multiplier (basis points) of the rental
+// when the share of reserved or rented cars reaches util (%)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Surge {
+    pub util: u32,
+    pub bps: u32,
+}
+
+// Pricing rules applied at the reservation and at the booking
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pricing {
+    pub weekend: u32,         // multiplier (basis points) of the daily rate on weekends
+    pub seasons: Vec<Season>, // sorted by start, without overlaps
+    pub surges: Vec<Surge>,   // sorted by util, the highest reached one is applied
+}
+
+// Aggregate reputation of a client
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reputation {
+    pub rentals: u32, // completed rentals
+    pub scores: u32,  // scored returns
+    pub total: u32,   // sum of the return scores
+}
+
+// Aggregate ratings of a car given by the clients
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rating {
+    pub count: u32,   // rated rentals
+    pub car: u32,     // sum of the car ratings
+    pub service: u32, // sum of the service ratings
+}
+
+// open_req approves the clients with at least count scored returns
+// and an average score * 100 of at least score
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutoAppr {
+    pub score: u32,
+    pub count: u32, // 0 disables the auto approval
+}
+
+// Discount (basis points) of the rental of the clients with at least rentals completed rentals
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Loyalty {
+    pub rentals: u32,
+    pub bps: u32,
+}
+
+// Price breakdown of a rental
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Quote {
+    pub days: u32,     // started days of the period
+    pub base: i128,    // daily rate * days
+    pub weekend: i128, // weekend extra
+    pub season: i128,  // seasonal extra
+    pub util: u32,     // share of reserved or rented cars (%)
+    pub surge: i128,   // surge extra
+    pub total: i128,   // base + weekend + season + surge
+}
+
+// Client request waiting for approval
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendReq {
+    pub client: Identifier,
+    pub submitted: u64,  // open_req timestamp
+    pub kyc: BytesN<32>, // hash of the off-chain KYC documents (zeros if not informed)
+}
+
+// Car returned by the list views, with its rental status
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CarInfo {
+    pub plate: Bytes,
+    pub car: CarDataKey,
+    pub status: CarStatus,
+    pub svc_due: bool, // the car is due for service
+}
 
 #[contracttype]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -26,6 +309,10 @@ pub enum ClientStatus {
     Pending = 0,
     Approved = 1,
     Declined = 2,
+    Unknown = 3,   // never opened a request
+    Suspended = 4, // can't reserve a car until reinstated
+    Revoked = 5,   // can't reserve a car or open a new request
+    Expired = 6,   // approval validity period ended, the client opens a new request
 }
 
 #[contracttype]
@@ -36,8 +323,43 @@ pub enum RentedCarStatus {
     Rented = 1,
     DropReview = 2,
     DropReviewDenied = 3,
+    InDispute = 4,
 }
 
+// Operator roles. The owner (contract admin) has all the roles
+// and grants or revokes the others. The roles of an operator are stored as a bitmask
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Role {
+    Owner = 1,
+    FleetManager = 2,
+    ReturnInspector = 4,
+    ClientApprover = 8,
+    Arbiter = 16,
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Outcome {
+    Completed = 0, // drop accepted
+    Cancelled = 1, // reservation cancelled by the client
+    Expired = 2,   // reservation expired without taking the car
+}
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum CarStatus {
+    Available = 0,
+    Reserved = 1,
+    Rented = 2,
+    DropReview = 3,
+    DropReviewDenied = 4,
+    InDispute = 5,
+    Maintenance = 6,
+}
 
 #[derive(Clone)]
 #[contracttype]
@@ -45,6 +367,39 @@ pub enum DataKey {
     Nonce(Identifier),
     Client(Identifier),
     Admin,
-    Car(Bytes),       // = CarDataKey
-    RentedCar(Bytes), // = RentedCarDataKey
+    Token,                // = BytesN<32>
+    Car(Bytes),           // = CarDataKey
+    RentedCar(Bytes),     // = RentedCarDataKey
+    Policy,               // = ResvPolicy
+    Plates,               // = Vec<Bytes>
+    Roles(Identifier),    // = u32 (bitmask of Role)
+    RentalCnt,            // = u32
+    Rental(u32),          // = RentalRec
+    CarHist(Bytes),       // = Vec<u32> (rental ids)
+    ClntHist(Identifier), // = Vec<u32> (rental ids)
+    Bookings(Bytes),      // = Vec<Booking> (sorted by start)
+    Active(Identifier),   // = Vec<Bytes> (plates reserved or rented by the client)
+    Booked(Identifier),   // = Vec<Bytes> (plates booked by the client)
+    Tier(Identifier),     // = u32
+    TierLimit(u32),       // = u32 (max active rentals of the clients of the tier)
+    InsurCnt,             // = u32 (last insurance product id)
+    Insurance(u32),       // = Insurance
+    Pool,                 // = i128 (claims pool balance)
+    Owed(Identifier),     // = i128 (charges owed by the client)
+    Weekend,              // = u32 (basis points)
+    Seasons,              // = Vec<Season> (sorted by start)
+    Surges,               // = Vec<Surge> (sorted by util)
+    ClntRep(Identifier),  // = Reputation
+    CarRating(Bytes),     // = Rating
+    AutoAppr,             // = AutoAppr
+    Loyalty,              // = Vec<Loyalty> (sorted by rentals)
+    ApprTtl,              // = u64 (approval validity period in seconds, 0 = no expiration)
+    ApprExp(Identifier),  // = u64 (approval expiration timestamp)
+    Pending,              // = Vec<PendReq> (in submission order)
+    Branches,             // = Vec<Bytes> (branch codes, in the order they were added)
+    Branch(Bytes),        // = i128 (relocation fee of the one-way rentals returned at the branch)
+    MaintWins(Bytes),     // = Vec<MaintWin> (sorted by start)
+    Services(Bytes),      // = Vec<ServiceRec> (service history of the car, from the oldest)
+    SvcState(Bytes),      // = SvcState (usage of the car since its last service)
+    SvcRule,              // = SvcRule
 }
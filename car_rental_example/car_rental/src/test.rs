@@ -1,9 +1,145 @@
 #![cfg(test)]
-use crate::contract::CarRentalContract;
-use crate::storage_types::{CarDataKey, ClientStatus, RentedCarStatus};
-use crate::testutils::CarRental;
-use soroban_auth::Identifier;
-use soroban_sdk::{testutils::Accounts, Env, IntoVal};
+use crate::contract::{token, CarRentalContract, CarRentalContractClient};
+use crate::metadata::write_car;
+use crate::storage_types::{
+    CarDataKey, CarSpec, CarStatus, ClientStatus, DataKey, Denial, Insurance, Loyalty, Outcome,
+    PendReq, Pricing, Quote, Rating, RentedCarStatus, Reputation, Role, Season, ServiceRec, Surge,
+    SvcRule, SvcState,
+};
+use crate::testutils::{CarRental, Signer};
+use core::fmt::Debug;
+use soroban_auth::{AccountSignatures, Ed25519Signature, Identifier, Signature};
+use soroban_sdk::testutils::{Accounts, Events, Ledger, LedgerInfo};
+use soroban_sdk::{symbol, vec, AccountId, Bytes, BytesN, Env, IntoVal, RawVal, Symbol, Vec};
+
+use token::{Client as TokenClient, TokenMetadata};
+
+fn create_token_contract(e: &Env, admin: &AccountId) -> (BytesN<32>, TokenClient) {
+    let id = e.register_contract_token(None);
+    let token = TokenClient::new(e, &id);
+
+    token.init(
+        &Identifier::Account(admin.clone()),
+        &TokenMetadata {
+            name: "USD Coin".into_val(e),
+            symbol: "USDC".into_val(e),
+            decimals: 7,
+        },
+    );
+
+    (id, token)
+}
+
+// Mint tokens to the client and approve the car rental contract to transfer them
+fn fund_client(
+    e: &Env,
+    token: &TokenClient,
+    token_id: &BytesN<32>,
+    token_admin: &AccountId,
+    contract_id: &BytesN<32>,
+    user_id: &Identifier,
+    user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+          + soroban_sdk::testutils::ed25519::Sign<
+        soroban_auth::SignaturePayload,
+        Signature = [u8; 64],
+    > + Debug),
+    amount: i128,
+) {
+    token
+        .with_source_account(token_admin)
+        .mint(&Signature::Invoker, &0, user_id, &amount);
+
+    let spender = Identifier::Contract(contract_id.clone());
+    let nonce = token.nonce(user_id);
+    let sig = soroban_auth::testutils::ed25519::sign(
+        e,
+        user_sign,
+        token_id,
+        symbol!("approve"),
+        (user_id, &nonce, &spender, &amount),
+    );
+    token.approve(&sig, &nonce, &spender, &amount);
+}
+
+// Create an account with an ed25519 signer, to sign calls with Signature::Account
+fn create_signer_account(
+    e: &Env,
+) -> (
+    AccountId,
+    impl soroban_auth::testutils::ed25519::Identifier
+        + soroban_sdk::testutils::ed25519::Sign<soroban_auth::SignaturePayload, Signature = [u8; 64]>
+        + Debug,
+) {
+    let (signer_id, signer) = soroban_auth::testutils::ed25519::generate(e);
+    let signer_key = match signer_id {
+        Identifier::Ed25519(key) => key,
+        _ => panic!("ed25519 identifier expected"),
+    };
+
+    let account = e.accounts().generate_and_create();
+    e.accounts().set_signer_weight(&account, &signer_key, 1);
+    e.accounts().set_thresholds(&account, 1, 1, 1);
+    (account, signer)
+}
+
+fn account_sign(
+    e: &Env,
+    account: &AccountId,
+    signer: &(impl soroban_auth::testutils::ed25519::Identifier
+          + soroban_sdk::testutils::ed25519::Sign<
+        soroban_auth::SignaturePayload,
+        Signature = [u8; 64],
+    > + Debug),
+    contract_id: &BytesN<32>,
+    function: Symbol,
+    args: impl IntoVal<Env, Vec<RawVal>>,
+) -> Signature {
+    match soroban_auth::testutils::ed25519::sign(e, signer, contract_id, function, args) {
+        Signature::Ed25519(signature) => Signature::Account(AccountSignatures {
+            account_id: account.clone(),
+            signatures: vec![e, signature],
+        }),
+        _ => panic!("ed25519 signature expected"),
+    }
+}
+
+// Last event published in the environment
+fn last_event(e: &Env) -> Vec<(BytesN<32>, Vec<RawVal>, RawVal)> {
+    let events = e.events().all();
+    events.slice(events.len() - 1..)
+}
+
+fn set_ledger_time(e: &Env, timestamp: u64) {
+    e.ledger().set(LedgerInfo {
+        timestamp,
+        protocol_version: 1,
+        sequence_number: 10,
+        network_passphrase: Default::default(),
+        base_reserve: 10,
+    });
+}
+
+// Car of the tests: a Volkswagen Gol at the Downtown branch, without late and usage fees
+fn test_car(env: &Env) -> CarDataKey {
+    CarDataKey {
+        model: "Volkswagen Gol".into_val(env),
+        horse: 80,
+        color: "Red".into_val(env),
+        daily_rate: 100,
+        deposit: 1000,
+        grace: 0,
+        late_rate: 0,
+        year: 2020,
+        mileage: 0,
+        category: "Economy".into_val(env),
+        location: "Downtown".into_val(env),
+        home: "Downtown".into_val(env),
+        maint: false,
+        km_rate: 0,
+        km_day: 0,
+        fuel_rate: 0,
+    }
+}
 
 #[test]
 fn test_success() {
@@ -13,10 +149,25 @@ fn test_success() {
     let car_rental = CarRental::new(&env, &contract_id);
 
     let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
     let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
 
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
     // Admin init the contract
-    car_rental.init(&Identifier::Account(admin.clone()));
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
 
     // User 1 opens a request to validate the account
     car_rental.open_request(user_1_id.clone(), &user_1_sign);
@@ -42,61 +193,77 @@ fn test_success() {
     );
 
     // Admin add Car 1
-    let car_1_data = CarDataKey {
-        model: "Volkswagen Gol".into_val(&env),
-        horse: 80,
-        color: "Red".into_val(&env),
-    };
+    let car_1_data = test_car(&env);
     let car_1_plate = "IYD8J01";
-    car_rental.add_car(&admin, &car_1_plate, car_1_data.clone());
-    assert_eq!(car_rental.read_car(&car_1_plate), car_1_data);
+    car_rental.add_car(&admin, car_1_plate, car_1_data.clone());
+    assert_eq!(car_rental.read_car(car_1_plate), car_1_data);
 
     // Admin add Car 2
     let car_2_data = CarDataKey {
         model: "Honda Civic".into_val(&env),
         horse: 200,
         color: "White".into_val(&env),
+        daily_rate: 250,
+        deposit: 2000,
+        ..test_car(&env)
     };
     let car_2_plate = "PGWN112";
-    car_rental.add_car(&admin, &car_2_plate, car_2_data.clone());
-    assert_eq!(car_rental.read_car(&car_2_plate), car_2_data);
+    car_rental.add_car(&admin, car_2_plate, car_2_data.clone());
+    assert_eq!(car_rental.read_car(car_2_plate), car_2_data);
 
-    // Client reserves Car 1
-    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, &car_1_plate);
-    let mut rented_car = car_rental.read_rent(&car_1_plate);
+    // Client reserves Car 1 for 3 days. The deposit goes to escrow
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, car_1_plate, 3);
+    let mut rented_car = car_rental.read_rent(car_1_plate);
     assert_eq!(rented_car.status, RentedCarStatus::Reserved);
     assert_eq!(rented_car.renter, user_1_id.clone());
+    assert_eq!(rented_car.deposit, 1000);
+    assert_eq!(token.balance(&user_1_id), 9000);
+    assert_eq!(
+        token.balance(&Identifier::Contract(contract_id.clone())),
+        1000
+    );
 
-    // Client take the Car 1
-    car_rental.take_car(user_1_id.clone(), &user_1_sign, &car_1_plate);
-    rented_car = car_rental.read_rent(&car_1_plate);
+    // Client take the Car 1. The rental is charged (3 days * 100)
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, car_1_plate, 0, 100);
+    rented_car = car_rental.read_rent(car_1_plate);
     assert_eq!(rented_car.status, RentedCarStatus::Rented);
     assert_eq!(rented_car.renter, user_1_id.clone());
+    assert_eq!(token.balance(&user_1_id), 8700);
+    assert_eq!(token.balance(&admin_id), 300);
 
     // Admin remove Car 2
-    car_rental.remove_car(&admin, &car_2_plate);
+    car_rental.remove_car(&admin, car_2_plate);
 
     // Client drop Car 1
-    car_rental.drop_car(user_1_id.clone(), &user_1_sign, &car_1_plate);
-    rented_car = car_rental.read_rent(&car_1_plate);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, car_1_plate, 0, 100);
+    rented_car = car_rental.read_rent(car_1_plate);
     assert_eq!(rented_car.status, RentedCarStatus::DropReview);
     assert_eq!(rented_car.renter, user_1_id.clone());
 
-    // Admin denies drop request
-    car_rental.deny_drop(&admin, &car_1_plate);
-    rented_car = car_rental.read_rent(&car_1_plate);
+    // Admin denies drop request with a damage report of 200 (reason code 1)
+    let evidence = BytesN::from_array(&env, &[7; 32]);
+    car_rental.deny_drop(&admin, car_1_plate, 1, 200, evidence.clone());
+    rented_car = car_rental.read_rent(car_1_plate);
     assert_eq!(rented_car.status, RentedCarStatus::DropReviewDenied);
     assert_eq!(rented_car.renter, user_1_id.clone());
+    assert_eq!(rented_car.deposit, 1000);
+    assert_eq!(rented_car.reason, 1);
+    assert_eq!(rented_car.dmg_cost, 200);
+    assert_eq!(rented_car.evidence, evidence);
 
-    // Client try drop Car 1 again
-    car_rental.drop_car(user_1_id.clone(), &user_1_sign, &car_1_plate);
-    rented_car = car_rental.read_rent(&car_1_plate);
+    // Client accepts the damage report. The cost is withheld from the deposit
+    car_rental.accept_damage(user_1_id.clone(), &user_1_sign, car_1_plate);
+    rented_car = car_rental.read_rent(car_1_plate);
     assert_eq!(rented_car.status, RentedCarStatus::DropReview);
     assert_eq!(rented_car.renter, user_1_id.clone());
+    assert_eq!(rented_car.deposit, 800);
+    assert_eq!(token.balance(&admin_id), 500);
 
-    // Admin accept drop request
-    car_rental.accept_drop(&admin, &car_1_plate);
-    assert_eq!(car_rental.has_rent(&car_1_plate), false);
+    // Admin accept drop request. The rest of the deposit is refunded
+    car_rental.accept_drop(&admin, car_1_plate, 0, 100);
+    assert!(!car_rental.has_rent(car_1_plate));
+    assert_eq!(token.balance(&user_1_id), 9500);
+    assert_eq!(token.balance(&Identifier::Contract(contract_id.clone())), 0);
 }
 
 // Init Failed (has_admin = True)
@@ -108,10 +275,11 @@ fn already_initialized() {
     let car_rental = CarRental::new(&env, &contract_id);
 
     let admin = env.accounts().generate();
-    car_rental.init(&Identifier::Account(admin.clone()));
+    let (token_id, _) = create_token_contract(&env, &admin);
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
 
     let admin_1 = env.accounts().generate();
-    car_rental.init(&Identifier::Account(admin_1.clone()));
+    car_rental.init(&Identifier::Account(admin_1.clone()), &token_id);
 }
 
 #[test]
@@ -122,18 +290,16 @@ fn add_car_fails_already_exists() {
     let car_rental = CarRental::new(&env, &contract_id);
 
     let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
 
-    car_rental.init(&Identifier::Account(admin.clone()));
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
 
-    let car_data = CarDataKey {
-        model: "Volkswagen Gol".into_val(&env),
-        horse: 80,
-        color: "Red".into_val(&env),
-    };
+    let car_data = test_car(&env);
     let car_plate = "IYD8J01";
 
-    car_rental.add_car(&admin, &car_plate, car_data.clone());
-    car_rental.add_car(&admin, &car_plate, car_data.clone());
+    car_rental.add_car(&admin, car_plate, car_data.clone());
+    car_rental.add_car(&admin, car_plate, car_data.clone());
 }
 
 #[test]
@@ -144,10 +310,11 @@ fn remove_car_fails_no_exist() {
     let car_rental = CarRental::new(&env, &contract_id);
 
     let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
 
-    car_rental.init(&Identifier::Account(admin.clone()));
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
 
-    car_rental.remove_car(&admin, &"IYD8J01");
+    car_rental.remove_car(&admin, "IYD8J01");
 }
 
 #[test]
@@ -158,14 +325,14 @@ fn take_car_fails_car_not_rented() {
     let car_rental = CarRental::new(&env, &contract_id);
 
     let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
     let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
 
-    car_rental.init(&Identifier::Account(admin.clone()));
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
 
-    car_rental.take_car(user_1_id.clone(), &user_1_sign, &"IYD8J01");
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
 }
 
-
 #[test]
 fn test_change_admin_successfully() {
     let env: Env = Default::default();
@@ -173,10 +340,11 @@ fn test_change_admin_successfully() {
     let car_rental = CarRental::new(&env, &contract_id);
 
     let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
     let new_admin_1 = env.accounts().generate();
     let (new_admin_2_id, _new_admin_2_sign) = soroban_auth::testutils::ed25519::generate(&env);
 
-    car_rental.init(&Identifier::Account(admin.clone()));
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
     car_rental.set_admin(&admin, Identifier::Account(new_admin_1.clone()));
     assert_eq!(
         car_rental.read_admin(),
@@ -185,3 +353,3696 @@ fn test_change_admin_successfully() {
     car_rental.set_admin(&new_admin_1, new_admin_2_id.clone());
     assert_eq!(car_rental.read_admin(), new_admin_2_id.clone());
 }
+
+#[test]
+#[should_panic(expected = "Status(ContractError(15))")]
+fn reserve_car_fails_zero_days() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(14))")]
+fn deny_drop_fails_amount_greater_than_deposit() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    let car_plate = "IYD8J01";
+    car_rental.add_car(&admin, car_plate, car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, car_plate, 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, car_plate, 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, car_plate, 0, 100);
+
+    // Try to report a damage greater than the deposit in escrow
+    car_rental.deny_drop(
+        &admin,
+        car_plate,
+        1,
+        1001,
+        BytesN::from_array(&env, &[0; 32]),
+    );
+}
+
+#[test]
+fn test_late_return() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    let car_plate = "IYD8J01";
+    car_rental.add_car(&admin, car_plate, car_data);
+
+    // One hour of grace period and 10 per hour late
+    car_rental.set_late(&admin, car_plate, 3600, 10);
+    let car_data = car_rental.read_car(car_plate);
+    assert_eq!(car_data.grace, 3600);
+    assert_eq!(car_data.late_rate, 10);
+
+    set_ledger_time(&env, 1000);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, car_plate, 2);
+    assert_eq!(car_rental.read_rent(car_plate).reserved, 1000);
+
+    set_ledger_time(&env, 5000);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, car_plate, 0, 100);
+    let mut rented_car = car_rental.read_rent(car_plate);
+    assert_eq!(rented_car.picked_up, 5000);
+    assert_eq!(rented_car.due, 5000 + 2 * 86400);
+    assert_eq!(token.balance(&admin_id), 200);
+
+    // Inside the grace period there are no charges
+    set_ledger_time(&env, rented_car.due + 3600);
+    assert_eq!(car_rental.charges(car_plate), 0);
+
+    // The hours after the grace period are charged: 1 second after it is charged as 1 hour
+    set_ledger_time(&env, rented_car.due + 3600 + 1);
+    assert_eq!(car_rental.charges(car_plate), 10);
+
+    // 3 hours and 1 second late are charged as 3 hours
+    set_ledger_time(&env, rented_car.due + 3 * 3600 + 1);
+    assert_eq!(car_rental.charges(car_plate), 30);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, car_plate, 0, 100);
+    rented_car = car_rental.read_rent(car_plate);
+    assert_eq!(rented_car.returned, rented_car.due + 3 * 3600 + 1);
+    assert_eq!(rented_car.late_fee, 30);
+
+    // The charges don't grow after the return
+    set_ledger_time(&env, rented_car.due + 10 * 3600);
+    assert_eq!(car_rental.charges(car_plate), 30);
+
+    // The late fee is taken from the deposit and the rest is refunded
+    car_rental.accept_drop(&admin, car_plate, 0, 100);
+    assert_eq!(token.balance(&admin_id), 230);
+    assert_eq!(token.balance(&user_1_id), 9770);
+    assert_eq!(token.balance(&Identifier::Contract(contract_id.clone())), 0);
+}
+
+#[test]
+fn test_late_fee_grace_boundary() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+    car_rental.set_late(&admin, "IYD8J01", 1800, 10);
+
+    // Returned at the end of the grace period: no late fee
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    set_ledger_time(&env, car_rental.read_rent("IYD8J01").due + 1800);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    assert_eq!(car_rental.read_rent("IYD8J01").late_fee, 0);
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+
+    // Returned 1 second after it: one started hour, the grace period is not charged
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    set_ledger_time(&env, car_rental.read_rent("IYD8J01").due + 1800 + 1);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    assert_eq!(car_rental.read_rent("IYD8J01").late_fee, 10);
+}
+
+#[test]
+fn test_charges_owed() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    // The client approves the deposit, the rental and only 100 of fees
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        1200,
+    );
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+    car_rental.set_late(&admin, "IYD8J01", 0, 500);
+
+    // 3 hours late: 1500 of late fee, above the deposit
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    set_ledger_time(&env, car_rental.read_rent("IYD8J01").due + 3 * 3600);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+
+    // The deposit and the allowance left are charged, the rest is owed and the drop is accepted
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (
+                    symbol!("accpt_drop"),
+                    Bytes::from_slice(&env, b"IYD8J01"),
+                    user_1_id.clone()
+                )
+                    .into_val(&env),
+                0_i128.into_val(&env)
+            ),
+        ]
+    );
+    assert!(!car_rental.has_rent("IYD8J01"));
+    assert_eq!(token.balance(&admin_id), 100 + 1000 + 100);
+    assert_eq!(token.balance(&user_1_id), 0);
+    assert_eq!(car_rental.owed(&user_1_id), 400);
+
+    // The client pays what is owed and can reserve again
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        2000,
+    );
+    car_rental.pay_owed(user_1_id.clone(), &user_1_sign);
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("pay_owed"), user_1_id.clone()).into_val(&env),
+                400_i128.into_val(&env)
+            ),
+        ]
+    );
+    assert_eq!(car_rental.owed(&user_1_id), 0);
+    assert_eq!(token.balance(&admin_id), 1600);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(43))")]
+fn reserve_car_fails_outstanding_balance() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    // The client approves the deposit, the rental and only 100 of fees
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        1200,
+    );
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+    car_rental.set_late(&admin, "IYD8J01", 0, 500);
+
+    // 3 hours late: 1500 of late fee, above the deposit
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    set_ledger_time(&env, car_rental.read_rent("IYD8J01").due + 3 * 3600);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+}
+
+#[test]
+fn test_reservation_expiry_and_cancellation() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    let (user_2_id, user_2_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    for (user_id, user_sign) in [(&user_1_id, &user_1_sign), (&user_2_id, &user_2_sign)] {
+        fund_client(
+            &env,
+            &token,
+            &token_id,
+            &token_admin,
+            &contract_id,
+            user_id,
+            user_sign,
+            10000,
+        );
+    }
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+    car_rental.open_request(user_2_id.clone(), &user_2_sign);
+    car_rental.approve_request(&admin, user_2_id.clone());
+
+    let car_data = test_car(&env);
+    let car_plate = "IYD8J01";
+    car_rental.add_car(&admin, car_plate, car_data);
+
+    // Reservations are kept for 2 hours, free cancellation in the first 10 minutes and 150 of fee after
+    car_rental.set_policy(&admin, 7200, 600, 150);
+    let policy = car_rental.policy();
+    assert_eq!(policy.ttl, 7200);
+    assert_eq!(policy.free, 600);
+    assert_eq!(policy.fee, 150);
+
+    // Cancel inside the free period refunds all the deposit
+    set_ledger_time(&env, 1000);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, car_plate, 1);
+    assert_eq!(car_rental.read_rent(car_plate).expires, 1000 + 7200);
+    set_ledger_time(&env, 1599);
+    car_rental.cancel_reservation(user_1_id.clone(), &user_1_sign, car_plate);
+    assert!(!car_rental.has_rent(car_plate));
+    assert_eq!(token.balance(&user_1_id), 10000);
+
+    // Cancel after the free period withholds the fee
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, car_plate, 1);
+    set_ledger_time(&env, 2199);
+    car_rental.cancel_reservation(user_1_id.clone(), &user_1_sign, car_plate);
+    assert!(!car_rental.has_rent(car_plate));
+    assert_eq!(token.balance(&user_1_id), 9850);
+    assert_eq!(token.balance(&admin_id), 150);
+
+    // An expired reservation is released by anyone
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, car_plate, 1);
+    set_ledger_time(&env, 2199 + 7200);
+    car_rental.release_expired(car_plate);
+    assert!(!car_rental.has_rent(car_plate));
+    assert_eq!(token.balance(&user_1_id), 9700);
+    assert_eq!(token.balance(&admin_id), 300);
+
+    // The next reservation takes over an expired reservation
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, car_plate, 1);
+    set_ledger_time(&env, 2199 + 2 * 7200);
+    car_rental.reserve_car(user_2_id.clone(), &user_2_sign, car_plate, 2);
+    let rented_car = car_rental.read_rent(car_plate);
+    assert_eq!(rented_car.renter, user_2_id.clone());
+    assert_eq!(rented_car.status, RentedCarStatus::Reserved);
+    assert_eq!(token.balance(&user_1_id), 9550);
+    assert_eq!(token.balance(&user_2_id), 9000);
+    assert_eq!(token.balance(&admin_id), 450);
+    assert_eq!(
+        token.balance(&Identifier::Contract(contract_id.clone())),
+        1000
+    );
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(16))")]
+fn release_expired_fails_not_expired() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    let car_plate = "IYD8J01";
+    car_rental.add_car(&admin, car_plate, car_data);
+
+    set_ledger_time(&env, 1000);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, car_plate, 1);
+    set_ledger_time(&env, 1000 + 86399);
+    car_rental.release_expired(car_plate);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(17))")]
+fn take_car_fails_reservation_expired() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    let car_plate = "IYD8J01";
+    car_rental.add_car(&admin, car_plate, car_data);
+
+    set_ledger_time(&env, 1000);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, car_plate, 1);
+    set_ledger_time(&env, 1000 + 86400);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, car_plate, 0, 100);
+}
+
+#[test]
+fn test_account_signature() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let client = CarRentalContractClient::new(&env, &contract_id);
+
+    let (admin, admin_signer) = create_signer_account(&env);
+    let admin_id = Identifier::Account(admin.clone());
+    let (token_id, _) = create_token_contract(&env, &admin);
+    client.init(&admin_id, &token_id);
+
+    let plate: Bytes = "IYD8J01".into_val(&env);
+    let model: Bytes = "Volkswagen Gol".into_val(&env);
+    let color: Bytes = "Red".into_val(&env);
+    let category: Bytes = "Economy".into_val(&env);
+    let home: Bytes = "Downtown".into_val(&env);
+    let sig = account_sign(
+        &env,
+        &admin,
+        &admin_signer,
+        &contract_id,
+        symbol!("set_branch"),
+        (&admin_id, &0_i128, &home, &0_i128),
+    );
+    client.set_branch(&sig, &0, &home, &0);
+
+    let car = CarSpec {
+        model: model.clone(),
+        color,
+        horse: 80,
+        year: 2020,
+        mileage: 0,
+        category,
+        home,
+        daily_rate: 100,
+        deposit: 1000,
+    };
+    let sig = account_sign(
+        &env,
+        &admin,
+        &admin_signer,
+        &contract_id,
+        symbol!("add_car"),
+        (&admin_id, &1_i128, &plate, &car),
+    );
+    client.add_car(&sig, &1, &plate, &car);
+    assert_eq!(client.read_car(&plate).model, model);
+
+    let sig = account_sign(
+        &env,
+        &admin,
+        &admin_signer,
+        &contract_id,
+        symbol!("remove_car"),
+        (&admin_id, &2_i128, &plate),
+    );
+    client.remove_car(&sig, &2, &plate);
+    assert_eq!(client.nonce(&admin_id), 3);
+}
+
+#[test]
+#[should_panic(expected = "Failed ED25519 verification")]
+fn account_signature_fails_tampered_plate() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let client = CarRentalContractClient::new(&env, &contract_id);
+
+    let (admin, admin_signer) = create_signer_account(&env);
+    let admin_id = Identifier::Account(admin.clone());
+    let (token_id, _) = create_token_contract(&env, &admin);
+    client.init(&admin_id, &token_id);
+
+    let car_rental = CarRental::new(&env, &contract_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data.clone());
+    car_rental.add_car(&admin, "PGWN112", car_data);
+
+    // The admin signs the removal of one car and a relayer submits it for another car
+    let signed_plate: Bytes = "IYD8J01".into_val(&env);
+    let sig = account_sign(
+        &env,
+        &admin,
+        &admin_signer,
+        &contract_id,
+        symbol!("remove_car"),
+        (&admin_id, &0_i128, &signed_plate),
+    );
+    client.remove_car(&sig, &0, &"PGWN112".into_val(&env));
+}
+
+#[test]
+#[should_panic(expected = "Failed ED25519 verification")]
+fn account_signature_fails_other_function() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let client = CarRentalContractClient::new(&env, &contract_id);
+
+    let (admin, admin_signer) = create_signer_account(&env);
+    let admin_id = Identifier::Account(admin.clone());
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+    client.init(&admin_id, &token_id);
+
+    let car_rental = CarRental::new(&env, &contract_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+
+    // A signature for accpt_drop can't be used to deny the drop
+    let plate: Bytes = "IYD8J01".into_val(&env);
+    let nonce = car_rental.nonce(&admin_id);
+    let sig = account_sign(
+        &env,
+        &admin,
+        &admin_signer,
+        &contract_id,
+        symbol!("accpt_drop"),
+        (&admin_id, &nonce, &plate, &0_u64, &100_u32),
+    );
+    client.deny_drop(
+        &sig,
+        &nonce,
+        &plate,
+        &3,
+        &600,
+        &BytesN::from_array(&env, &[9; 32]),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Failed ED25519 verification")]
+fn ed25519_signature_fails_tampered_days() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    // The client signs a reservation of 1 day and a relayer submits it for 30 days
+    let plate: Bytes = "IYD8J01".into_val(&env);
+    let nonce = car_rental.nonce(&user_1_id);
+    let sig = soroban_auth::testutils::ed25519::sign(
+        &env,
+        &user_1_sign,
+        &contract_id,
+        symbol!("resrve_car"),
+        (
+            &user_1_id,
+            &nonce,
+            &plate,
+            &1_u32,
+            &0_u32,
+            &Bytes::new(&env),
+        ),
+    );
+    CarRentalContractClient::new(&env, &contract_id).resrve_car(
+        &sig,
+        &nonce,
+        &plate,
+        &30,
+        &0,
+        &Bytes::new(&env),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Failed ED25519 verification")]
+fn ed25519_signature_fails_tampered_plate() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data.clone());
+    car_rental.add_car(&admin, "PGWN112", car_data);
+
+    // The client signs a reservation of one car and a relayer submits it for another car
+    let signed_plate: Bytes = "IYD8J01".into_val(&env);
+    let nonce = car_rental.nonce(&user_1_id);
+    let sig = soroban_auth::testutils::ed25519::sign(
+        &env,
+        &user_1_sign,
+        &contract_id,
+        symbol!("resrve_car"),
+        (
+            &user_1_id,
+            &nonce,
+            &signed_plate,
+            &1_u32,
+            &0_u32,
+            &Bytes::new(&env),
+        ),
+    );
+    CarRentalContractClient::new(&env, &contract_id).resrve_car(
+        &sig,
+        &nonce,
+        &"PGWN112".into_val(&env),
+        &1,
+        &0,
+        &Bytes::new(&env),
+    );
+}
+
+#[test]
+fn test_ed25519_signer() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    // The admin, an inspector and the client sign their calls with Ed25519 keys
+    let admin = car_rental.generate_signer();
+    let inspector = car_rental.generate_signer();
+    let user_1 = car_rental.generate_signer();
+
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1.id,
+        &user_1.key,
+        10000,
+    );
+
+    car_rental.init(&admin.id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.grant_role(&admin, inspector.id.clone(), Role::ReturnInspector);
+    car_rental.open_request(user_1.id.clone(), &user_1.key);
+    car_rental.approve_request(&admin, user_1.id.clone());
+    assert_eq!(car_rental.nonce(&admin.id), 3);
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+    car_rental.set_late(&admin, "IYD8J01", 3600, 10);
+    assert_eq!(car_rental.nonce(&admin.id), 5);
+
+    car_rental.reserve_car(user_1.id.clone(), &user_1.key, "IYD8J01", 1);
+    car_rental.take_car(user_1.id.clone(), &user_1.key, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1.id.clone(), &user_1.key, "IYD8J01", 0, 100);
+    car_rental.accept_drop(&inspector, "IYD8J01", 0, 100);
+    assert!(!car_rental.has_rent("IYD8J01"));
+    assert_eq!(car_rental.nonce(&inspector.id), 1);
+    assert_eq!(car_rental.nonce(&user_1.id), 4);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(3))")]
+fn signed_call_fails_nonce_replay() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let client = CarRentalContractClient::new(&env, &contract_id);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = car_rental.generate_signer();
+    let (token_id, _) = create_token_contract(&env, &env.accounts().generate());
+    car_rental.init(&admin.id, &token_id);
+
+    let nonce = car_rental.nonce(&admin.id);
+    let sig = admin.sign(
+        &env,
+        &contract_id,
+        symbol!("set_wkend"),
+        (&admin.id, &nonce, &12000_u32),
+    );
+    client.set_wkend(&sig, &nonce, &12000);
+    assert_eq!(car_rental.pricing().weekend, 12000);
+
+    // A relayer submits the same signed call again
+    client.set_wkend(&sig, &nonce, &12000);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(3))")]
+fn signed_call_fails_wrong_nonce() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let client = CarRentalContractClient::new(&env, &contract_id);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = car_rental.generate_signer();
+    let (token_id, _) = create_token_contract(&env, &env.accounts().generate());
+    car_rental.init(&admin.id, &token_id);
+
+    // The admin signs the call with a nonce ahead of the contract
+    let nonce = car_rental.nonce(&admin.id) + 1;
+    let sig = admin.sign(
+        &env,
+        &contract_id,
+        symbol!("set_wkend"),
+        (&admin.id, &nonce, &12000_u32),
+    );
+    client.set_wkend(&sig, &nonce, &12000);
+}
+
+#[test]
+#[should_panic(expected = "Failed ED25519 verification")]
+fn signed_call_fails_wrong_signer() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let client = CarRentalContractClient::new(&env, &contract_id);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = car_rental.generate_signer();
+    let other = car_rental.generate_signer();
+    let (token_id, _) = create_token_contract(&env, &env.accounts().generate());
+    car_rental.init(&admin.id, &token_id);
+
+    // Another key signs the call on behalf of the admin
+    let nonce = car_rental.nonce(&admin.id);
+    let sig = other.sign(
+        &env,
+        &contract_id,
+        symbol!("set_wkend"),
+        (&admin.id, &nonce, &12000_u32),
+    );
+    let sig = match (admin.identifier(&env), sig) {
+        (Identifier::Ed25519(public_key), Signature::Ed25519(other_sig)) => {
+            Signature::Ed25519(Ed25519Signature {
+                public_key,
+                signature: other_sig.signature,
+            })
+        }
+        _ => panic!("ed25519 signature expected"),
+    };
+    client.set_wkend(&sig, &nonce, &12000);
+}
+
+#[test]
+fn test_list_cars() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    let (user_2_id, _) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data.clone());
+    car_rental.add_car(&admin, "PGWN112", car_data.clone());
+    car_rental.add_car(&admin, "ABC1234", car_data.clone());
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "PGWN112", 1);
+
+    let cars = car_rental.list_cars(0, 10);
+    assert_eq!(cars.len(), 3);
+    let car = cars.get_unchecked(1).unwrap();
+    assert_eq!(car.plate, "PGWN112".into_val(&env));
+    assert_eq!(car.car, car_data);
+    assert_eq!(car.status, CarStatus::Reserved);
+    assert_eq!(cars.get_unchecked(0).unwrap().status, CarStatus::Available);
+
+    // Pagination
+    let cars = car_rental.list_cars(1, 1);
+    assert_eq!(cars.len(), 1);
+    assert_eq!(
+        cars.get_unchecked(0).unwrap().plate,
+        "PGWN112".into_val(&env)
+    );
+    assert_eq!(car_rental.list_cars(3, 10).len(), 0);
+
+    let available = car_rental.list_available(0, 10);
+    assert_eq!(available.len(), 2);
+    assert_eq!(
+        available.get_unchecked(0).unwrap().plate,
+        "IYD8J01".into_val(&env)
+    );
+    assert_eq!(
+        available.get_unchecked(1).unwrap().plate,
+        "ABC1234".into_val(&env)
+    );
+    let available = car_rental.list_available(1, 10);
+    assert_eq!(available.len(), 1);
+    assert_eq!(
+        available.get_unchecked(0).unwrap().plate,
+        "ABC1234".into_val(&env)
+    );
+
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "PGWN112", 0, 100);
+    let rented = car_rental.rented_by(&user_1_id, 0, 10);
+    assert_eq!(rented.len(), 1);
+    assert_eq!(rented.get_unchecked(0).unwrap().status, CarStatus::Rented);
+    assert_eq!(car_rental.rented_by(&user_1_id, 1, 10).len(), 0);
+    assert_eq!(car_rental.rented_by(&user_2_id, 0, 10).len(), 0);
+
+    // A free car in maintenance is not rented by anyone
+    car_rental.set_maint(&admin, "ABC1234", true);
+    assert_eq!(car_rental.rented_by(&user_1_id, 0, 10).len(), 1);
+    assert_eq!(car_rental.rented_by(&user_2_id, 0, 10).len(), 0);
+
+    // Removed cars leave the index
+    car_rental.remove_car(&admin, "IYD8J01");
+    let cars = car_rental.list_cars(0, 10);
+    assert_eq!(cars.len(), 2);
+    assert_eq!(
+        cars.get_unchecked(0).unwrap().plate,
+        "PGWN112".into_val(&env)
+    );
+}
+
+#[test]
+fn test_roles() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let fleet_manager = env.accounts().generate();
+    let fleet_manager_id = Identifier::Account(fleet_manager.clone());
+    let approver = env.accounts().generate();
+    let approver_id = Identifier::Account(approver.clone());
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+
+    // The owner has all the roles
+    assert!(car_rental.has_role(&admin_id, Role::Owner));
+    assert!(car_rental.has_role(&admin_id, Role::ReturnInspector));
+    assert!(!car_rental.has_role(&fleet_manager_id, Role::FleetManager));
+
+    car_rental.grant_role(&admin, fleet_manager_id.clone(), Role::FleetManager);
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("grant_role"), fleet_manager_id.clone()).into_val(&env),
+                Role::FleetManager.into_val(&env)
+            ),
+        ]
+    );
+    car_rental.grant_role(&admin, approver_id.clone(), Role::ClientApprover);
+    assert!(car_rental.has_role(&fleet_manager_id, Role::FleetManager));
+    assert!(!car_rental.has_role(&fleet_manager_id, Role::ClientApprover));
+    assert!(!car_rental.has_role(&fleet_manager_id, Role::Owner));
+
+    // Each operator executes the calls of its role
+    let car_data = test_car(&env);
+    car_rental.add_car(&fleet_manager, "IYD8J01", car_data.clone());
+    assert_eq!(car_rental.read_car("IYD8J01"), car_data);
+
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&approver, user_1_id.clone());
+    assert_eq!(
+        car_rental.read_client(user_1_id.clone()),
+        ClientStatus::Approved
+    );
+
+    car_rental.revoke_role(&admin, fleet_manager_id.clone(), Role::FleetManager);
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("revk_role"), fleet_manager_id.clone()).into_val(&env),
+                Role::FleetManager.into_val(&env)
+            ),
+        ]
+    );
+    assert!(!car_rental.has_role(&fleet_manager_id, Role::FleetManager));
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(1))")]
+fn approve_request_fails_without_role() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let fleet_manager = env.accounts().generate();
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.grant_role(
+        &admin,
+        Identifier::Account(fleet_manager.clone()),
+        Role::FleetManager,
+    );
+
+    // A fleet manager can't approve clients
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&fleet_manager, user_1_id.clone());
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(1))")]
+fn add_car_fails_role_revoked() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let fleet_manager = env.accounts().generate();
+    let fleet_manager_id = Identifier::Account(fleet_manager.clone());
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.grant_role(&admin, fleet_manager_id.clone(), Role::FleetManager);
+    car_rental.revoke_role(&admin, fleet_manager_id.clone(), Role::FleetManager);
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&fleet_manager, "IYD8J01", car_data);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(18))")]
+fn grant_role_fails_owner() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let operator = env.accounts().generate();
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.grant_role(&admin, Identifier::Account(operator.clone()), Role::Owner);
+}
+
+#[test]
+fn test_arbiter_role() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let arbiter = env.accounts().generate();
+    let arbiter_id = Identifier::Account(arbiter.clone());
+
+    car_rental.init(&admin_id, &token_id);
+
+    // The owner is not an arbiter, the disputes are settled by another operator
+    assert!(!car_rental.has_role(&admin_id, Role::Arbiter));
+    car_rental.grant_role(&admin, arbiter_id.clone(), Role::Arbiter);
+    assert!(car_rental.has_role(&arbiter_id, Role::Arbiter));
+    assert!(!car_rental.has_role(&arbiter_id, Role::ReturnInspector));
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(1))")]
+fn settle_fails_owner() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.deny_drop(
+        &admin,
+        "IYD8J01",
+        3,
+        600,
+        BytesN::from_array(&env, &[9; 32]),
+    );
+    car_rental.dispute(user_1_id.clone(), &user_1_sign, "IYD8J01");
+
+    // The owner denied the drop, it can't settle the dispute
+    car_rental.settle(&admin, "IYD8J01", 250);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(42))")]
+fn grant_role_fails_inspector_arbiter() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let operator = env.accounts().generate();
+    let operator_id = Identifier::Account(operator.clone());
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.grant_role(&admin, operator_id.clone(), Role::ReturnInspector);
+    car_rental.grant_role(&admin, operator_id, Role::Arbiter);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(42))")]
+fn grant_role_fails_arbiter_inspector() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let operator = env.accounts().generate();
+    let operator_id = Identifier::Account(operator.clone());
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.grant_role(&admin, operator_id.clone(), Role::Arbiter);
+    car_rental.grant_role(&admin, operator_id, Role::ReturnInspector);
+}
+
+#[test]
+fn test_events() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("open_req"), user_1_id.clone()).into_val(&env),
+                ().into_val(&env)
+            ),
+        ]
+    );
+
+    car_rental.deny_request(&admin, user_1_id.clone());
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("deny_req"), user_1_id.clone()).into_val(&env),
+                ().into_val(&env)
+            ),
+        ]
+    );
+
+    car_rental.approve_request(&admin, user_1_id.clone());
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("appr_req"), user_1_id.clone()).into_val(&env),
+                ().into_val(&env)
+            ),
+        ]
+    );
+
+    let car_data = test_car(&env);
+    let plate: Bytes = "IYD8J01".into_val(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data.clone());
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("add_car"), plate.clone()).into_val(&env),
+                car_data.clone().into_val(&env)
+            ),
+        ]
+    );
+
+    car_rental.set_late(&admin, "IYD8J01", 3600, 500);
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("set_late"), plate.clone()).into_val(&env),
+                (3600_u64, 500_i128).into_val(&env)
+            ),
+        ]
+    );
+
+    car_rental.set_usage(&admin, "IYD8J01", 2, 300, 10);
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("set_usage"), plate.clone()).into_val(&env),
+                (2_i128, 300_u64, 10_i128).into_val(&env)
+            ),
+        ]
+    );
+
+    car_rental.add_car(&admin, "PGWN112", car_data.clone());
+    car_rental.remove_car(&admin, "PGWN112");
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("remove_car"), Bytes::from_slice(&env, b"PGWN112")).into_val(&env),
+                ().into_val(&env)
+            ),
+        ]
+    );
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 2);
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("resrve_car"), plate.clone(), user_1_id.clone()).into_val(&env),
+                2_u32.into_val(&env)
+            ),
+        ]
+    );
+
+    set_ledger_time(&env, 1000);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("take_car"), plate.clone(), user_1_id.clone()).into_val(&env),
+                (1000_u64 + 2 * 86400).into_val(&env)
+            ),
+        ]
+    );
+
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("drop_car"), plate.clone(), user_1_id.clone()).into_val(&env),
+                0_i128.into_val(&env)
+            ),
+        ]
+    );
+
+    let evidence = BytesN::from_array(&env, &[7; 32]);
+    car_rental.deny_drop(&admin, "IYD8J01", 1, 200, evidence.clone());
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("deny_drop"), plate.clone(), user_1_id.clone()).into_val(&env),
+                (1_u32, 200_i128, evidence).into_val(&env)
+            ),
+        ]
+    );
+
+    car_rental.accept_damage(user_1_id.clone(), &user_1_sign, "IYD8J01");
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("accpt_dmg"), plate.clone(), user_1_id.clone()).into_val(&env),
+                200_i128.into_val(&env)
+            ),
+        ]
+    );
+
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("accpt_drop"), plate.clone(), user_1_id.clone()).into_val(&env),
+                800_i128.into_val(&env)
+            ),
+        ]
+    );
+
+    car_rental.set_policy(&admin, 7200, 1800, 100);
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("set_policy"),).into_val(&env),
+                (7200_u64, 1800_u64, 100_i128).into_val(&env)
+            ),
+        ]
+    );
+
+    let new_admin_id = Identifier::Account(env.accounts().generate());
+    car_rental.set_admin(&admin, new_admin_id.clone());
+    assert_eq!(
+        last_event(&env),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol!("set_admin"), admin_id.clone()).into_val(&env),
+                new_admin_id.into_val(&env)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_rental_history() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    let (user_2_id, user_2_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    for (user_id, user_sign) in [(&user_1_id, &user_1_sign), (&user_2_id, &user_2_sign)] {
+        fund_client(
+            &env,
+            &token,
+            &token_id,
+            &token_admin,
+            &contract_id,
+            user_id,
+            user_sign,
+            10000,
+        );
+    }
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+    car_rental.open_request(user_2_id.clone(), &user_2_sign);
+    car_rental.approve_request(&admin, user_2_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data.clone());
+    car_rental.add_car(&admin, "PGWN112", car_data);
+
+    // User 1 rents Car 1 with one denied drop
+    set_ledger_time(&env, 1000);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    set_ledger_time(&env, 2000);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    set_ledger_time(&env, 3000);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.deny_drop(
+        &admin,
+        "IYD8J01",
+        2,
+        300,
+        BytesN::from_array(&env, &[1; 32]),
+    );
+    car_rental.accept_damage(user_1_id.clone(), &user_1_sign, "IYD8J01");
+    set_ledger_time(&env, 4000);
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+
+    // User 2 reserves Car 1 and cancels it, then User 1 reserves Car 2 and cancels it
+    car_rental.reserve_car(user_2_id.clone(), &user_2_sign, "IYD8J01", 2);
+    car_rental.cancel_reservation(user_2_id.clone(), &user_2_sign, "IYD8J01");
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "PGWN112", 1);
+    car_rental.cancel_reservation(user_1_id.clone(), &user_1_sign, "PGWN112");
+
+    let history = car_rental.car_history("IYD8J01", 0, 10);
+    assert_eq!(history.len(), 2);
+    let rental = history.get_unchecked(0).unwrap();
+    assert_eq!(rental.plate, "IYD8J01".into_val(&env));
+    assert_eq!(rental.renter, user_1_id.clone());
+    assert_eq!(rental.outcome, Outcome::Completed);
+    assert_eq!(rental.reserved, 1000);
+    assert_eq!(rental.picked_up, 2000);
+    assert_eq!(rental.due, 2000 + 86400);
+    assert_eq!(rental.returned, 3000);
+    assert_eq!(rental.closed, 4000);
+    assert_eq!(rental.denials.len(), 1);
+    assert_eq!(rental.denials.get_unchecked(0).unwrap().reason, 2);
+    assert_eq!(rental.withheld, 300);
+    let rental = history.get_unchecked(1).unwrap();
+    assert_eq!(rental.renter, user_2_id.clone());
+    assert_eq!(rental.outcome, Outcome::Cancelled);
+    assert_eq!(rental.picked_up, 0);
+
+    let history = car_rental.client_history(&user_1_id, 0, 10);
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        history.get_unchecked(0).unwrap().outcome,
+        Outcome::Completed
+    );
+    assert_eq!(
+        history.get_unchecked(1).unwrap().plate,
+        "PGWN112".into_val(&env)
+    );
+    assert_eq!(
+        history.get_unchecked(1).unwrap().outcome,
+        Outcome::Cancelled
+    );
+
+    // Pagination
+    let history = car_rental.client_history(&user_1_id, 1, 1);
+    assert_eq!(history.len(), 1);
+    assert_eq!(
+        history.get_unchecked(0).unwrap().plate,
+        "PGWN112".into_val(&env)
+    );
+    assert_eq!(car_rental.car_history("PGWN112", 1, 10).len(), 0);
+}
+
+#[test]
+fn test_rental_history_denials() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+
+    // The drop is denied twice, each damage report is kept
+    set_ledger_time(&env, 1000);
+    car_rental.deny_drop(
+        &admin,
+        "IYD8J01",
+        2,
+        300,
+        BytesN::from_array(&env, &[1; 32]),
+    );
+    car_rental.accept_damage(user_1_id.clone(), &user_1_sign, "IYD8J01");
+    set_ledger_time(&env, 2000);
+    car_rental.deny_drop(
+        &admin,
+        "IYD8J01",
+        4,
+        200,
+        BytesN::from_array(&env, &[2; 32]),
+    );
+    car_rental.accept_damage(user_1_id.clone(), &user_1_sign, "IYD8J01");
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+
+    let rental = car_rental
+        .car_history("IYD8J01", 0, 10)
+        .get_unchecked(0)
+        .unwrap();
+    assert_eq!(rental.withheld, 500);
+    assert_eq!(
+        rental.denials,
+        vec![
+            &env,
+            Denial {
+                reason: 2,
+                cost: 300,
+                evidence: BytesN::from_array(&env, &[1; 32]),
+                denied: 1000,
+            },
+            Denial {
+                reason: 4,
+                cost: 200,
+                evidence: BytesN::from_array(&env, &[2; 32]),
+                denied: 2000,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_damage_dispute() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let inspector = env.accounts().generate();
+    let arbiter = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.grant_role(
+        &admin,
+        Identifier::Account(inspector.clone()),
+        Role::ReturnInspector,
+    );
+    car_rental.grant_role(&admin, Identifier::Account(arbiter.clone()), Role::Arbiter);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+
+    // The inspector reports a damage of 600 and the client disputes it
+    car_rental.deny_drop(
+        &inspector,
+        "IYD8J01",
+        3,
+        600,
+        BytesN::from_array(&env, &[9; 32]),
+    );
+    car_rental.dispute(user_1_id.clone(), &user_1_sign, "IYD8J01");
+    let rented_car = car_rental.read_rent("IYD8J01");
+    assert_eq!(rented_car.status, RentedCarStatus::InDispute);
+    assert_eq!(rented_car.deposit, 1000);
+
+    // The arbiter settles the dispute withholding 250
+    car_rental.settle(&arbiter, "IYD8J01", 250);
+    let rented_car = car_rental.read_rent("IYD8J01");
+    assert_eq!(rented_car.status, RentedCarStatus::DropReview);
+    assert_eq!(rented_car.deposit, 750);
+    assert_eq!(rented_car.withheld, 250);
+    assert_eq!(token.balance(&admin_id), 100 + 250);
+
+    car_rental.accept_drop(&inspector, "IYD8J01", 0, 100);
+    assert_eq!(token.balance(&user_1_id), 10000 - 100 - 250);
+    assert_eq!(token.balance(&Identifier::Contract(contract_id.clone())), 0);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(14))")]
+fn settle_fails_amount_greater_than_cost() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let arbiter = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.grant_role(&admin, Identifier::Account(arbiter.clone()), Role::Arbiter);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.deny_drop(
+        &admin,
+        "IYD8J01",
+        3,
+        600,
+        BytesN::from_array(&env, &[9; 32]),
+    );
+    car_rental.dispute(user_1_id.clone(), &user_1_sign, "IYD8J01");
+
+    car_rental.settle(&arbiter, "IYD8J01", 601);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(12))")]
+fn drop_car_fails_after_denied_drop() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.deny_drop(
+        &admin,
+        "IYD8J01",
+        3,
+        600,
+        BytesN::from_array(&env, &[9; 32]),
+    );
+
+    // The client must accept or dispute the damage report
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+}
+
+#[test]
+fn test_update_car_and_maintenance() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.set_branch(&admin, "Airport", 0);
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+    car_rental.set_late(&admin, "IYD8J01", 3600, 20);
+
+    // The late fee settings and the maintenance flag are kept by update_car
+    let new_data = CarDataKey {
+        color: "Blue".into_val(&env),
+        daily_rate: 120,
+        deposit: 1500,
+        year: 2021,
+        mileage: 15000,
+        category: "Compact".into_val(&env),
+        location: "Airport".into_val(&env),
+        home: "Airport".into_val(&env),
+        ..test_car(&env)
+    };
+    car_rental.update_car(&admin, "IYD8J01", new_data.clone());
+    let car = car_rental.read_car("IYD8J01");
+    assert_eq!(car.color, new_data.color);
+    assert_eq!(car.daily_rate, 120);
+    assert_eq!(car.deposit, 1500);
+    assert_eq!(car.year, 2021);
+    assert_eq!(car.mileage, 15000);
+    assert_eq!(car.category, new_data.category);
+    assert_eq!(car.home, new_data.home);
+    // the car stays at its current branch until relocated
+    assert_eq!(car.location, Bytes::from_slice(&env, b"Downtown"));
+    assert_eq!(car.grace, 3600);
+    assert_eq!(car.late_rate, 20);
+    assert!(!car.maint);
+
+    // A car in maintenance is not available
+    car_rental.set_maint(&admin, "IYD8J01", true);
+    assert!(car_rental.read_car("IYD8J01").maint);
+    assert_eq!(car_rental.list_available(0, 10).len(), 0);
+    let cars = car_rental.list_cars(0, 10);
+    assert_eq!(
+        cars.get_unchecked(0).unwrap().status,
+        CarStatus::Maintenance
+    );
+
+    car_rental.set_maint(&admin, "IYD8J01", false);
+    assert_eq!(car_rental.list_available(0, 10).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(21))")]
+fn reserve_car_fails_car_in_maintenance() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+    car_rental.set_maint(&admin, "IYD8J01", true);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(7))")]
+fn update_car_fails_car_rented() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data.clone());
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+
+    car_rental.update_car(&admin, "IYD8J01", car_data);
+}
+
+#[test]
+fn test_unknown_client() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+
+    assert_eq!(
+        car_rental.read_client(user_1_id.clone()),
+        ClientStatus::Unknown
+    );
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    assert_eq!(
+        car_rental.read_client(user_1_id.clone()),
+        ClientStatus::Pending
+    );
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(22))")]
+fn reserve_car_fails_client_not_found() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(22))")]
+fn approve_request_fails_client_not_found() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, _) = soroban_auth::testutils::ed25519::generate(&env);
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+
+    car_rental.approve_request(&admin, user_1_id.clone());
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(6))")]
+fn read_car_fails_car_not_exists() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+
+    car_rental.read_car("IYD8J01");
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(8))")]
+fn read_rent_fails_car_not_rented() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.read_rent("IYD8J01");
+}
+
+#[test]
+fn test_client_suspension() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+
+    // A suspended client finishes the current rental
+    car_rental.suspend_client(&admin, user_1_id.clone());
+    assert_eq!(
+        car_rental.read_client(user_1_id.clone()),
+        ClientStatus::Suspended
+    );
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+    assert!(!car_rental.has_rent("IYD8J01"));
+
+    // A reinstated client can reserve a car again
+    car_rental.reinstate_client(&admin, user_1_id.clone());
+    assert_eq!(
+        car_rental.read_client(user_1_id.clone()),
+        ClientStatus::Approved
+    );
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+
+    car_rental.revoke_client(&admin, user_1_id.clone());
+    assert_eq!(
+        car_rental.read_client(user_1_id.clone()),
+        ClientStatus::Revoked
+    );
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(13))")]
+fn reserve_car_fails_client_suspended() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+    car_rental.suspend_client(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(23))")]
+fn open_request_fails_client_approved() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(23))")]
+fn approve_request_fails_client_revoked() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.revoke_client(&admin, user_1_id.clone());
+
+    car_rental.approve_request(&admin, user_1_id.clone());
+}
+
+#[test]
+fn test_bookings() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    let (user_2_id, user_2_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    for (user_id, user_sign) in [(&user_1_id, &user_1_sign), (&user_2_id, &user_2_sign)] {
+        fund_client(
+            &env,
+            &token,
+            &token_id,
+            &token_admin,
+            &contract_id,
+            user_id,
+            user_sign,
+            10000,
+        );
+    }
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+    car_rental.open_request(user_2_id.clone(), &user_2_sign);
+    car_rental.approve_request(&admin, user_2_id.clone());
+    car_rental.set_policy(&admin, 86400, 3600, 100);
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    // A client holds several future bookings, kept sorted by the start of the period
+    let day = 86400;
+    set_ledger_time(&env, 1000);
+    car_rental.book_car(
+        user_1_id.clone(),
+        &user_1_sign,
+        "IYD8J01",
+        20 * day,
+        21 * day,
+    );
+    car_rental.book_car(
+        user_1_id.clone(),
+        &user_1_sign,
+        "IYD8J01",
+        10 * day,
+        12 * day,
+    );
+    let bookings = car_rental.bookings("IYD8J01");
+    assert_eq!(bookings.len(), 2);
+    assert_eq!(bookings.get_unchecked(0).unwrap().start, 10 * day);
+    assert_eq!(bookings.get_unchecked(1).unwrap().start, 20 * day);
+    assert_eq!(token.balance(&user_1_id), 8000);
+
+    assert!(!car_rental.available("IYD8J01", 11 * day, 13 * day));
+    assert!(car_rental.available("IYD8J01", 12 * day, 20 * day));
+    assert!(car_rental.available("IYD8J01", 0, 2 * day));
+
+    // The car is taken inside the booked period and must be returned until its end
+    set_ledger_time(&env, 10 * day + 5);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    let rented_car = car_rental.read_rent("IYD8J01");
+    assert_eq!(rented_car.status, RentedCarStatus::Rented);
+    assert_eq!(rented_car.days, 2);
+    assert_eq!(rented_car.due, 12 * day);
+    assert_eq!(car_rental.bookings("IYD8J01").len(), 1);
+    assert_eq!(token.balance(&user_1_id), 7800);
+
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+    assert_eq!(token.balance(&user_1_id), 8800);
+
+    // Cancelling after the free period withholds the fee
+    car_rental.cancel_booking(user_1_id.clone(), &user_1_sign, "IYD8J01", 20 * day);
+    assert_eq!(car_rental.bookings("IYD8J01").len(), 0);
+    assert_eq!(token.balance(&user_1_id), 9700);
+    assert_eq!(token.balance(&admin_id), 300);
+
+    // A booking not taken until the end of the period is released as expired
+    car_rental.book_car(
+        user_2_id.clone(),
+        &user_2_sign,
+        "IYD8J01",
+        30 * day,
+        31 * day,
+    );
+    set_ledger_time(&env, 31 * day);
+    car_rental.release_expired("IYD8J01");
+    assert_eq!(car_rental.bookings("IYD8J01").len(), 0);
+    assert_eq!(token.balance(&user_2_id), 9900);
+    assert_eq!(token.balance(&Identifier::Contract(contract_id.clone())), 0);
+
+    let history = car_rental.car_history("IYD8J01", 0, 10);
+    assert_eq!(history.len(), 3);
+    assert_eq!(
+        history.get_unchecked(0).unwrap().outcome,
+        Outcome::Completed
+    );
+    assert_eq!(
+        history.get_unchecked(1).unwrap().outcome,
+        Outcome::Cancelled
+    );
+    assert_eq!(history.get_unchecked(2).unwrap().outcome, Outcome::Expired);
+}
+
+#[test]
+fn test_booking_expired_car_held() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    let (user_2_id, user_2_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    for (user_id, user_sign) in [(&user_1_id, &user_1_sign), (&user_2_id, &user_2_sign)] {
+        fund_client(
+            &env,
+            &token,
+            &token_id,
+            &token_admin,
+            &contract_id,
+            user_id,
+            user_sign,
+            10000,
+        );
+    }
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+    car_rental.open_request(user_2_id.clone(), &user_2_sign);
+    car_rental.approve_request(&admin, user_2_id.clone());
+    car_rental.set_policy(&admin, 86400, 3600, 100);
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    let day = 86400;
+    set_ledger_time(&env, 1000);
+    car_rental.book_car(
+        user_1_id.clone(),
+        &user_1_sign,
+        "IYD8J01",
+        10 * day,
+        12 * day,
+    );
+    car_rental.book_car(
+        user_2_id.clone(),
+        &user_2_sign,
+        "IYD8J01",
+        12 * day,
+        13 * day,
+    );
+
+    // User 1 returns the car after the booked period of user 2
+    set_ledger_time(&env, 10 * day);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    set_ledger_time(&env, 13 * day + 5);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+
+    // The booking of user 2 expires without the fee, the car was not free in its period
+    car_rental.release_expired("IYD8J01");
+    assert_eq!(car_rental.bookings("IYD8J01").len(), 0);
+    assert_eq!(token.balance(&user_2_id), 10000);
+    assert_eq!(token.balance(&Identifier::Contract(contract_id.clone())), 0);
+    let rental = car_rental
+        .client_history(&user_2_id, 0, 10)
+        .get_unchecked(0)
+        .unwrap();
+    assert_eq!(rental.outcome, Outcome::Expired);
+    assert_eq!(rental.withheld, 0);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(24))")]
+fn book_car_fails_overlap() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    let (user_2_id, user_2_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    for (user_id, user_sign) in [(&user_1_id, &user_1_sign), (&user_2_id, &user_2_sign)] {
+        fund_client(
+            &env,
+            &token,
+            &token_id,
+            &token_admin,
+            &contract_id,
+            user_id,
+            user_sign,
+            10000,
+        );
+    }
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+    car_rental.open_request(user_2_id.clone(), &user_2_sign);
+    car_rental.approve_request(&admin, user_2_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.book_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 864000, 1036800);
+    car_rental.book_car(user_2_id.clone(), &user_2_sign, "IYD8J01", 950400, 1123200);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(26))")]
+fn take_car_fails_outside_booking() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.book_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 864000, 1036800);
+    set_ledger_time(&env, 863999);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(26))")]
+fn take_car_fails_after_booking() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.book_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 864000, 1036800);
+    // the booking is not taken before its end
+    set_ledger_time(&env, 1123200);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+}
+
+#[test]
+fn test_usage_pricing() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = CarDataKey {
+        mileage: 1000,
+        ..test_car(&env)
+    };
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    // 100 km a day included, 2 for each km above it and 5 for each fuel level point missing
+    car_rental.set_usage(&admin, "IYD8J01", 2, 100, 5);
+    let car = car_rental.read_car("IYD8J01");
+    assert_eq!(car.km_rate, 2);
+    assert_eq!(car.km_day, 100);
+    assert_eq!(car.fuel_rate, 5);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 2);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1000, 100);
+    let rented_car = car_rental.read_rent("IYD8J01");
+    assert_eq!(rented_car.odo_out, 1000);
+    assert_eq!(rented_car.fuel_out, 100);
+    assert_eq!(rented_car.price, 200);
+
+    // The charges are estimated with the readings reported by the client
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1300, 90);
+    assert_eq!(car_rental.charges("IYD8J01"), 100 * 2 + 10 * 5);
+
+    // The inspector confirms other readings: 150 km above the allowance and 20 points of fuel missing
+    car_rental.accept_drop(&admin, "IYD8J01", 1350, 80);
+    assert_eq!(car_rental.read_car("IYD8J01").mileage, 1350);
+    assert_eq!(token.balance(&admin_id), 200 + 300 + 100);
+    assert_eq!(token.balance(&user_1_id), 10000 - 200 - 400);
+    assert_eq!(token.balance(&Identifier::Contract(contract_id.clone())), 0);
+
+    let rental = car_rental
+        .car_history("IYD8J01", 0, 1)
+        .get_unchecked(0)
+        .unwrap();
+    let invoice = car_rental.invoice(rental.id);
+    assert_eq!(invoice.plate, "IYD8J01".into_val(&env));
+    assert_eq!(invoice.renter, user_1_id);
+    assert_eq!(invoice.days, 2);
+    assert_eq!(invoice.price, 200);
+    assert_eq!(invoice.late_fee, 0);
+    assert_eq!(invoice.km, 350);
+    assert_eq!(invoice.km_fee, 300);
+    assert_eq!(invoice.fuel_fee, 100);
+    assert_eq!(invoice.damage, 0);
+    assert_eq!(invoice.total, 600);
+    assert_eq!(invoice.refunded, 600);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(27))")]
+fn take_car_fails_invalid_reading() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = CarDataKey {
+        mileage: 1000,
+        ..test_car(&env)
+    };
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    // The odometer can't be lower than the car mileage
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 999, 100);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(29))")]
+fn invoice_fails_rental_not_completed() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.cancel_reservation(user_1_id.clone(), &user_1_sign, "IYD8J01");
+
+    car_rental.invoice(0);
+}
+
+#[test]
+fn test_rental_limit() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data.clone());
+    car_rental.add_car(&admin, "IYD8J02", car_data.clone());
+    car_rental.add_car(&admin, "IYD8J03", car_data);
+
+    // Without limit the client can reserve any number of cars
+    assert_eq!(car_rental.client_tier(user_1_id.clone()), 0);
+    assert_eq!(car_rental.tier_limit(0), u32::MAX);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J02", 1);
+    assert_eq!(
+        car_rental.active(user_1_id.clone()),
+        vec![&env, "IYD8J01".into_val(&env), "IYD8J02".into_val(&env)]
+    );
+
+    // A rented car is still active, a returned car is not
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    assert_eq!(car_rental.active(user_1_id.clone()).len(), 2);
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+    assert_eq!(
+        car_rental.active(user_1_id.clone()),
+        vec![&env, "IYD8J02".into_val(&env)]
+    );
+
+    // The tier 1 allows two active rentals
+    car_rental.set_limit(&admin, 1, 2);
+    car_rental.set_tier(&admin, user_1_id.clone(), 1);
+    assert_eq!(car_rental.client_tier(user_1_id.clone()), 1);
+    assert_eq!(car_rental.tier_limit(1), 2);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    assert_eq!(car_rental.active(user_1_id.clone()).len(), 2);
+
+    // Cancelling a reservation frees a slot
+    car_rental.cancel_reservation(user_1_id.clone(), &user_1_sign, "IYD8J02");
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J03", 1);
+    assert_eq!(
+        car_rental.active(user_1_id.clone()),
+        vec![&env, "IYD8J01".into_val(&env), "IYD8J03".into_val(&env)]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(30))")]
+fn reserve_car_fails_rental_limit_reached() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+    car_rental.set_limit(&admin, 0, 1);
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data.clone());
+    car_rental.add_car(&admin, "IYD8J02", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J02", 1);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(30))")]
+fn book_car_fails_rental_limit_reached() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+    car_rental.set_limit(&admin, 0, 2);
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data.clone());
+    car_rental.add_car(&admin, "IYD8J02", car_data.clone());
+    car_rental.add_car(&admin, "IYD8J03", car_data);
+
+    // The bookings count in the limit with the active rentals
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.book_car(user_1_id.clone(), &user_1_sign, "IYD8J02", 86400, 172800);
+    car_rental.book_car(user_1_id.clone(), &user_1_sign, "IYD8J03", 86400, 172800);
+}
+
+#[test]
+fn test_insurance_claim() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    let insurance = car_rental.add_insurance(&admin, 200, 300);
+    assert_eq!(insurance, 1);
+    assert_eq!(
+        car_rental.insurance(insurance),
+        Insurance {
+            premium: 200,
+            deduct: 300,
+        }
+    );
+
+    // The premium goes to the claims pool
+    car_rental.reserve_insured(user_1_id.clone(), &user_1_sign, "IYD8J01", 1, insurance);
+    assert_eq!(car_rental.pool(), 200);
+    assert_eq!(token.balance(&user_1_id), 10000 - 1000 - 200);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+
+    // The pool pays the damage above the deductible, the deductible is withheld from the deposit
+    car_rental.deny_drop(
+        &admin,
+        "IYD8J01",
+        3,
+        400,
+        BytesN::from_array(&env, &[9; 32]),
+    );
+    car_rental.accept_damage(user_1_id.clone(), &user_1_sign, "IYD8J01");
+    let rented_car = car_rental.read_rent("IYD8J01");
+    assert_eq!(rented_car.covered, 100);
+    assert_eq!(rented_car.withheld, 300);
+    assert_eq!(rented_car.deposit, 700);
+    assert_eq!(car_rental.pool(), 100);
+    assert_eq!(token.balance(&admin_id), 100 + 400);
+
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+    let invoice = car_rental.invoice(0);
+    assert_eq!(invoice.premium, 200);
+    assert_eq!(invoice.damage, 300);
+    assert_eq!(invoice.covered, 100);
+    assert_eq!(invoice.total, 100 + 200 + 300);
+    assert_eq!(token.balance(&user_1_id), 10000 - 100 - 200 - 300);
+    assert_eq!(
+        token.balance(&Identifier::Contract(contract_id.clone())),
+        100
+    );
+
+    // The premium of a cancelled reservation or booking is refunded
+    car_rental.reserve_insured(user_1_id.clone(), &user_1_sign, "IYD8J01", 1, insurance);
+    assert_eq!(car_rental.pool(), 300);
+    car_rental.cancel_reservation(user_1_id.clone(), &user_1_sign, "IYD8J01");
+    assert_eq!(car_rental.pool(), 100);
+    assert_eq!(token.balance(&user_1_id), 10000 - 100 - 200 - 300);
+    car_rental.book_insured(
+        user_1_id.clone(),
+        &user_1_sign,
+        "IYD8J01",
+        86400,
+        172800,
+        insurance,
+    );
+    assert_eq!(car_rental.pool(), 300);
+    assert_eq!(
+        car_rental
+            .bookings("IYD8J01")
+            .get_unchecked(0)
+            .unwrap()
+            .premium,
+        200
+    );
+    car_rental.cancel_booking(user_1_id.clone(), &user_1_sign, "IYD8J01", 86400);
+    assert_eq!(car_rental.pool(), 100);
+    assert_eq!(token.balance(&user_1_id), 10000 - 100 - 200 - 300);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(31))")]
+fn reserve_car_fails_insurance_not_exists() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_insured(user_1_id.clone(), &user_1_sign, "IYD8J01", 1, 1);
+}
+
+#[test]
+fn test_pricing_rules() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data.clone());
+    car_rental.add_car(&admin, "IYD8J02", car_data);
+
+    // The ledger starts on a thursday: friday is in a season (2x), saturday is a weekend day (1.5x)
+    car_rental.set_weekend(&admin, 15000);
+    car_rental.add_season(&admin, 86400, 2 * 86400, 20000);
+    car_rental.set_surge(&admin, 50, 12000);
+    assert_eq!(
+        car_rental.quote("IYD8J01", 0, 3 * 86400),
+        Quote {
+            days: 3,
+            base: 300,
+            weekend: 50,
+            season: 100,
+            util: 0,
+            surge: 0,
+            total: 450,
+        }
+    );
+
+    // The price is quoted at the reservation and charged at the pickup
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J02", 3);
+    assert_eq!(car_rental.read_rent("IYD8J02").price, 450);
+
+    // Half of the cars are reserved: the surge is applied to the next reservation
+    let quote = car_rental.quote("IYD8J01", 0, 3 * 86400);
+    assert_eq!(quote.util, 50);
+    assert_eq!(quote.surge, 90);
+    assert_eq!(quote.total, 540);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 3);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    assert_eq!(car_rental.read_rent("IYD8J01").price, 540);
+    assert_eq!(token.balance(&admin_id), 540);
+    assert_eq!(token.balance(&user_1_id), 10000 - 2 * 1000 - 540);
+    assert_eq!(car_rental.quote("IYD8J01", 0, 3 * 86400).util, 100);
+
+    // An expired reservation not released yet doesn't count in the share of cars
+    set_ledger_time(&env, 86400);
+    assert_eq!(car_rental.quote("IYD8J01", 0, 3 * 86400).util, 50);
+
+    assert_eq!(
+        car_rental.pricing(),
+        Pricing {
+            weekend: 15000,
+            seasons: vec![
+                &env,
+                Season {
+                    start: 86400,
+                    end: 2 * 86400,
+                    bps: 20000,
+                },
+            ],
+            surges: vec![
+                &env,
+                Surge {
+                    util: 50,
+                    bps: 12000
+                }
+            ],
+        }
+    );
+
+    // A multiplier of 1x removes the surge
+    car_rental.remove_season(&admin, 86400);
+    car_rental.set_surge(&admin, 50, 10000);
+    assert_eq!(
+        car_rental.pricing(),
+        Pricing {
+            weekend: 15000,
+            seasons: vec![&env],
+            surges: vec![&env],
+        }
+    );
+    assert_eq!(car_rental.quote("IYD8J01", 0, 3 * 86400).total, 350);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(32))")]
+fn add_season_fails_overlap() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.add_season(&admin, 0, 2 * 86400, 20000);
+    car_rental.add_season(&admin, 86400, 3 * 86400, 15000);
+}
+
+#[test]
+fn test_ratings_and_reputation() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+
+    // The inspector scores the return and the client rates the car and the service
+    car_rental.score_return(&admin, 0, 5);
+    car_rental.rate_rental(user_1_id.clone(), &user_1_sign, 0, 4, 5);
+    let rental = car_rental
+        .client_history(&user_1_id, 0, 1)
+        .get_unchecked(0)
+        .unwrap();
+    assert_eq!((rental.score, rental.car_rate, rental.svc_rate), (5, 4, 5));
+    assert_eq!(
+        car_rental.reputation(user_1_id.clone()),
+        Reputation {
+            rentals: 1,
+            scores: 1,
+            total: 5,
+        }
+    );
+    assert_eq!(
+        car_rental.car_rating("IYD8J01"),
+        Rating {
+            count: 1,
+            car: 4,
+            service: 5,
+        }
+    );
+
+    // One completed rental gives a discount of 10%
+    car_rental.set_loyalty(&admin, 1, 1000);
+    assert_eq!(
+        car_rental.loyalty(),
+        vec![
+            &env,
+            Loyalty {
+                rentals: 1,
+                bps: 1000
+            }
+        ]
+    );
+    assert_eq!(car_rental.discount(user_1_id.clone()), 1000);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    assert_eq!(car_rental.read_rent("IYD8J01").price, 90);
+    car_rental.cancel_reservation(user_1_id.clone(), &user_1_sign, "IYD8J01");
+
+    // A declined client with a good reputation is approved by open_req, a new client is pending
+    car_rental.set_auto_approval(&admin, 450, 1);
+    car_rental.deny_request(&admin, user_1_id.clone());
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    assert_eq!(
+        car_rental.read_client(user_1_id.clone()),
+        ClientStatus::Approved
+    );
+    let (user_2_id, user_2_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    car_rental.open_request(user_2_id.clone(), &user_2_sign);
+    assert_eq!(car_rental.read_client(user_2_id), ClientStatus::Pending);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(35))")]
+fn score_return_fails_already_rated() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+
+    car_rental.score_return(&admin, 0, 5);
+    car_rental.score_return(&admin, 0, 1);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(34))")]
+fn rate_rental_fails_invalid_score() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+
+    car_rental.rate_rental(user_1_id.clone(), &user_1_sign, 0, 6, 5);
+}
+
+#[test]
+fn test_pending_requests() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    let (user_2_id, user_2_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    let (user_3_id, user_3_sign) = soroban_auth::testutils::ed25519::generate(&env);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    set_ledger_time(&env, 1000);
+    car_rental.open_request_kyc(
+        user_1_id.clone(),
+        &user_1_sign,
+        BytesN::from_array(&env, &[7; 32]),
+    );
+    set_ledger_time(&env, 2000);
+    car_rental.open_request(user_2_id.clone(), &user_2_sign);
+    car_rental.open_request(user_3_id.clone(), &user_3_sign);
+
+    // The requests wait in the queue from the oldest
+    assert_eq!(car_rental.pending(0, 10).len(), 3);
+    assert_eq!(
+        car_rental.pending(0, 2),
+        vec![
+            &env,
+            PendReq {
+                client: user_1_id.clone(),
+                submitted: 1000,
+                kyc: BytesN::from_array(&env, &[7; 32]),
+            },
+            PendReq {
+                client: user_2_id.clone(),
+                submitted: 2000,
+                kyc: BytesN::from_array(&env, &[0; 32]),
+            },
+        ]
+    );
+
+    // A new request of a pending client goes to the end of the queue
+    set_ledger_time(&env, 3000);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    let requests = car_rental.pending(0, 10);
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests.get_unchecked(2).unwrap().client, user_1_id);
+    assert_eq!(requests.get_unchecked(2).unwrap().submitted, 3000);
+
+    // The admin decides the requests with one call
+    car_rental.batch_requests(
+        &admin,
+        vec![&env, user_1_id.clone(), user_3_id.clone()],
+        vec![&env, user_2_id.clone()],
+    );
+    assert_eq!(car_rental.read_client(user_1_id), ClientStatus::Approved);
+    assert_eq!(car_rental.read_client(user_2_id), ClientStatus::Declined);
+    assert_eq!(car_rental.read_client(user_3_id), ClientStatus::Approved);
+    assert_eq!(car_rental.pending(0, 10).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(22))")]
+fn batch_requests_fails_client_not_found() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    let (user_2_id, _) = soroban_auth::testutils::ed25519::generate(&env);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.batch_requests(&admin, vec![&env, user_1_id, user_2_id], vec![&env]);
+}
+
+#[test]
+fn test_approval_expiry() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_validity(&admin, 86400);
+    set_ledger_time(&env, 1000);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+    assert_eq!(car_rental.approval_expiry(user_1_id.clone()), 1000 + 86400);
+    assert_eq!(
+        car_rental.read_client(user_1_id.clone()),
+        ClientStatus::Approved
+    );
+
+    // After the validity period the client opens a new request
+    set_ledger_time(&env, 1000 + 86400);
+    assert_eq!(
+        car_rental.read_client(user_1_id.clone()),
+        ClientStatus::Expired
+    );
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    assert_eq!(
+        car_rental.read_client(user_1_id.clone()),
+        ClientStatus::Pending
+    );
+    assert_eq!(car_rental.pending(0, 10).len(), 1);
+    car_rental.approve_request(&admin, user_1_id.clone());
+    assert_eq!(
+        car_rental.approval_expiry(user_1_id.clone()),
+        1000 + 2 * 86400
+    );
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(13))")]
+fn reserve_car_fails_approval_expired() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.set_validity(&admin, 86400);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    set_ledger_time(&env, 86400);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+}
+
+#[test]
+fn test_plate_canonicalization() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+
+    // The car is added with the canonical plate and found with any spelling of it
+    car_rental.add_car(&admin, "iyd-8j 01", car_data.clone());
+    let cars = car_rental.list_cars(0, 10);
+    assert_eq!(
+        cars.get_unchecked(0).unwrap().plate,
+        "IYD8J01".into_val(&env)
+    );
+    assert_eq!(car_rental.read_car("IYD.8J01"), car_data);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "iyd8j01", 1);
+    assert!(car_rental.has_rent("IYD 8J01"));
+    assert_eq!(
+        car_rental.active(user_1_id.clone()),
+        vec![&env, "IYD8J01".into_val(&env)]
+    );
+
+    // A car added before the canonicalization is moved to its canonical plate
+    let legacy: Bytes = "pgw-n112".into_val(&env);
+    env.as_contract(&contract_id, || write_car(&env, &legacy, car_data.clone()));
+    let cars = car_rental.list_cars(0, 10);
+    assert_eq!(cars.get_unchecked(1).unwrap().plate, legacy);
+    car_rental.migrate_plate(&admin, "pgw-n112");
+    let cars = car_rental.list_cars(0, 10);
+    assert_eq!(cars.len(), 2);
+    assert_eq!(
+        cars.get_unchecked(1).unwrap().plate,
+        "PGWN112".into_val(&env)
+    );
+    assert_eq!(car_rental.read_car("pgwn112"), car_data);
+
+    // A legacy plate with characters not allowed anymore is migrated too,
+    // even when the car is missing from the plate index
+    let legacy: Bytes = "abc/123".into_val(&env);
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .set(DataKey::Car(legacy.clone()), car_data.clone())
+    });
+    assert_eq!(car_rental.list_cars(0, 10).len(), 2);
+    car_rental.migrate_plate(&admin, "abc/123");
+    let cars = car_rental.list_cars(0, 10);
+    assert_eq!(cars.len(), 3);
+    assert_eq!(
+        cars.get_unchecked(2).unwrap().plate,
+        "ABC123".into_val(&env)
+    );
+    assert_eq!(car_rental.read_car("abc123"), car_data);
+
+    // A legacy car is removed by its stored plate
+    let legacy: Bytes = "xyz/789".into_val(&env);
+    env.as_contract(&contract_id, || write_car(&env, &legacy, car_data.clone()));
+    car_rental.remove_car(&admin, "xyz/789");
+    assert_eq!(car_rental.list_cars(0, 10).len(), 3);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(36))")]
+fn add_car_fails_invalid_plate() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD_8J01", car_data);
+}
+
+#[test]
+fn test_branches() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let admin_id = Identifier::Account(admin.clone());
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&admin_id, &token_id);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    // The relocation fee of a branch is changed by adding it again
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.set_branch(&admin, "Airport", 50);
+    car_rental.set_branch(&admin, "Airport", 80);
+    assert_eq!(
+        car_rental.branches(),
+        vec![&env, "Downtown".into_val(&env), "Airport".into_val(&env)]
+    );
+    assert_eq!(car_rental.relocation_fee("Airport"), 80);
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data.clone());
+    let mut airport_car = car_data;
+    airport_car.location = "Airport".into_val(&env);
+    airport_car.home = "Airport".into_val(&env);
+    car_rental.add_car(&admin, "PGWN112", airport_car);
+
+    let available = car_rental.list_available_at("Airport", 0, 10);
+    assert_eq!(available.len(), 1);
+    assert_eq!(
+        available.get_unchecked(0).unwrap().plate,
+        "PGWN112".into_val(&env)
+    );
+    assert_eq!(car_rental.list_available(0, 10).len(), 2);
+
+    // A one-way rental pays the relocation fee of the drop-off branch at the pickup
+    car_rental.reserve_one_way(user_1_id.clone(), &user_1_sign, "IYD8J01", 1, 0, "Airport");
+    let rented_car = car_rental.read_rent("IYD8J01");
+    assert_eq!(rented_car.pickup, "Downtown".into_val(&env));
+    assert_eq!(rented_car.dropoff, "Airport".into_val(&env));
+    assert_eq!(rented_car.reloc, 80);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    assert_eq!(token.balance(&admin_id), 100 + 80);
+
+    // The car stays at the drop-off branch
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+    car_rental.accept_drop(&admin, "IYD8J01", 0, 100);
+    let car = car_rental.read_car("IYD8J01");
+    assert_eq!(car.location, "Airport".into_val(&env));
+    assert_eq!(car.home, "Downtown".into_val(&env));
+    assert_eq!(car_rental.list_available_at("Airport", 0, 10).len(), 2);
+    assert_eq!(car_rental.list_available_at("Downtown", 0, 10).len(), 0);
+    assert_eq!(token.balance(&user_1_id), 10000 - 100 - 80);
+
+    let rental = car_rental
+        .car_history("IYD8J01", 0, 1)
+        .get_unchecked(0)
+        .unwrap();
+    let invoice = car_rental.invoice(rental.id);
+    assert_eq!(invoice.reloc, 80);
+    assert_eq!(invoice.total, 180);
+
+    // A round trip doesn't pay the relocation fee
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    assert_eq!(
+        car_rental.read_rent("IYD8J01").dropoff,
+        "Airport".into_val(&env)
+    );
+    assert_eq!(car_rental.read_rent("IYD8J01").reloc, 0);
+    car_rental.cancel_reservation(user_1_id.clone(), &user_1_sign, "IYD8J01");
+
+    // The fleet manager moves the car back to its home branch and the unused branch is removed
+    car_rental.relocate_car(&admin, "IYD8J01", "Downtown");
+    assert_eq!(
+        car_rental.read_car("IYD8J01").location,
+        "Downtown".into_val(&env)
+    );
+    car_rental.set_branch(&admin, "Harbor", 0);
+    car_rental.remove_branch(&admin, "Harbor");
+    assert_eq!(car_rental.branches().len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(37))")]
+fn reserve_car_fails_branch_not_exists() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    car_rental.reserve_one_way(user_1_id.clone(), &user_1_sign, "IYD8J01", 1, 0, "Harbor");
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(38))")]
+fn remove_branch_fails_branch_in_use() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.set_branch(&admin, "Airport", 50);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    // A booking returns the car at the branch
+    car_rental.book_one_way(
+        user_1_id.clone(),
+        &user_1_sign,
+        "IYD8J01",
+        864000,
+        950400,
+        0,
+        "",
+        "Airport",
+    );
+    car_rental.remove_branch(&admin, "Airport");
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(39))")]
+fn take_car_fails_car_not_at_branch() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.set_branch(&admin, "Airport", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    // The car is booked at the airport but was not moved there
+    car_rental.book_one_way(
+        user_1_id.clone(),
+        &user_1_sign,
+        "IYD8J01",
+        864000,
+        950400,
+        0,
+        "Airport",
+        "",
+    );
+    set_ledger_time(&env, 864000);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 0, 100);
+}
+
+#[test]
+fn test_maintenance() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = CarDataKey {
+        mileage: 1000,
+        ..test_car(&env)
+    };
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    // The car is due for service after 2 rentals or 1000 km
+    car_rental.set_service_rule(&admin, 2, 1000);
+    assert_eq!(
+        car_rental.service_rule(),
+        SvcRule {
+            rentals: 2,
+            km: 1000
+        }
+    );
+
+    // A scheduled window blocks the bookings that overlap it
+    let day = 86400;
+    car_rental.add_maintenance(&admin, "IYD8J01", 10 * day, 12 * day);
+    car_rental.add_maintenance(&admin, "IYD8J01", 5 * day, 6 * day);
+    let windows = car_rental.maintenance_windows("IYD8J01");
+    assert_eq!(windows.len(), 2);
+    assert_eq!(windows.get_unchecked(0).unwrap().start, 5 * day);
+    assert!(!car_rental.available("IYD8J01", 11 * day, 13 * day));
+    assert!(car_rental.available("IYD8J01", 12 * day, 13 * day));
+    car_rental.remove_maintenance(&admin, "IYD8J01", 5 * day);
+    assert_eq!(car_rental.maintenance_windows("IYD8J01").len(), 1);
+
+    // The car is out of service inside the window
+    set_ledger_time(&env, 10 * day + 5);
+    let cars = car_rental.list_cars(0, 10);
+    assert_eq!(
+        cars.get_unchecked(0).unwrap().status,
+        CarStatus::Maintenance
+    );
+    assert_eq!(car_rental.list_available(0, 10).len(), 0);
+
+    // The completed service closes the window
+    car_rental.service_done(&admin, "IYD8J01", 1200, 300);
+    assert_eq!(car_rental.maintenance_windows("IYD8J01").len(), 0);
+    assert_eq!(car_rental.read_car("IYD8J01").mileage, 1200);
+    assert_eq!(car_rental.list_available(0, 10).len(), 1);
+
+    // Each completed rental counts in the usage since the last service
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1200, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1500, 100);
+    car_rental.accept_drop(&admin, "IYD8J01", 1500, 100);
+    let state = car_rental.service_state("IYD8J01");
+    assert_eq!(state.rentals, 1);
+    assert_eq!(state.km, 300);
+    assert!(!state.due);
+
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1);
+    car_rental.take_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1500, 100);
+    car_rental.drop_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 1700, 100);
+    car_rental.accept_drop(&admin, "IYD8J01", 1700, 100);
+    assert!(car_rental.service_state("IYD8J01").due);
+    assert!(
+        car_rental
+            .list_cars(0, 10)
+            .get_unchecked(0)
+            .unwrap()
+            .svc_due
+    );
+
+    // The service resets the usage and is kept in the service history
+    set_ledger_time(&env, 13 * day);
+    car_rental.service_done(&admin, "IYD8J01", 1800, 250);
+    assert_eq!(
+        car_rental.service_state("IYD8J01"),
+        SvcState {
+            rentals: 0,
+            km: 0,
+            due: false,
+        }
+    );
+    let services = car_rental.service_history("IYD8J01", 0, 10);
+    assert_eq!(services.len(), 2);
+    assert_eq!(
+        services.get_unchecked(1).unwrap(),
+        ServiceRec {
+            done: 13 * day,
+            mileage: 1800,
+            cost: 250,
+            rentals: 2,
+            km: 500,
+        }
+    );
+    assert_eq!(car_rental.service_history("IYD8J01", 1, 10).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(40))")]
+fn reserve_car_fails_maintenance_overlap() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let token_admin = env.accounts().generate();
+    let (token_id, token) = create_token_contract(&env, &token_admin);
+    let (user_1_id, user_1_sign) = soroban_auth::testutils::ed25519::generate(&env);
+    fund_client(
+        &env,
+        &token,
+        &token_id,
+        &token_admin,
+        &contract_id,
+        &user_1_id,
+        &user_1_sign,
+        10000,
+    );
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.set_branch(&admin, "Downtown", 0);
+    car_rental.open_request(user_1_id.clone(), &user_1_sign);
+    car_rental.approve_request(&admin, user_1_id.clone());
+
+    let car_data = test_car(&env);
+    car_rental.add_car(&admin, "IYD8J01", car_data);
+
+    // The latest return of the reservation is after the start of the window
+    car_rental.add_maintenance(&admin, "IYD8J01", 172800, 259200);
+    car_rental.reserve_car(user_1_id.clone(), &user_1_sign, "IYD8J01", 2);
+}
+
+#[test]
+#[should_panic(expected = "Status(ContractError(41))")]
+fn remove_maintenance_fails_not_exists() {
+    let env: Env = Default::default();
+    let contract_id = env.register_contract(None, CarRentalContract);
+    let car_rental = CarRental::new(&env, &contract_id);
+
+    let admin = env.accounts().generate();
+    let (token_id, _) = create_token_contract(&env, &admin);
+
+    car_rental.init(&Identifier::Account(admin.clone()), &token_id);
+    car_rental.remove_maintenance(&admin, "IYD8J01", 864000);
+}
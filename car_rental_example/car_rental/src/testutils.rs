@@ -1,9 +1,106 @@
 #![cfg(any(test, feature = "testutils"))]
 use crate::contract::CarRentalContractClient;
-use crate::storage_types::{CarDataKey, ClientStatus, RentedCarDataKey};
+use crate::storage_types::{
+    AutoAppr, Booking, CarDataKey, CarInfo, CarSpec, ClientStatus, Insurance, Invoice, Loyalty,
+    MaintWin, PendReq, Pricing, Quote, Rating, RentalRec, RentedCarDataKey, Reputation, ResvPolicy,
+    Role, ServiceRec, SvcRule, SvcState,
+};
 use core::fmt::Debug;
-use soroban_auth::{Identifier, Signature};
-use soroban_sdk::{symbol, BigInt, BytesN, Env, IntoVal};
+use soroban_auth::{Identifier, Signature, SignaturePayload};
+use soroban_sdk::testutils::ed25519::Sign;
+use soroban_sdk::{symbol, AccountId, Bytes, BytesN, Env, IntoVal, RawVal, Symbol, Vec};
+
+// Signer of the admin calls. The payload signed is the contract id, the function name
+// and the arguments of the call, starting with the identifier of the signer and its nonce
+pub trait Signer {
+    fn identifier(&self, env: &Env) -> Identifier;
+    // next nonce expected by the contract
+    fn nonce(&self, env: &Env, contract_id: &BytesN<32>) -> i128;
+    fn sign(
+        &self,
+        env: &Env,
+        contract_id: &BytesN<32>,
+        function: Symbol,
+        args: impl IntoVal<Env, Vec<RawVal>>,
+    ) -> Signature;
+    // client of the contract used to submit the call
+    fn client(&self, env: &Env, contract_id: &BytesN<32>) -> CarRentalContractClient;
+}
+
+// An account invokes the contract with the Invoker signature, that always uses the nonce 0
+impl Signer for AccountId {
+    fn identifier(&self, _env: &Env) -> Identifier {
+        Identifier::Account(self.clone())
+    }
+
+    fn nonce(&self, _env: &Env, _contract_id: &BytesN<32>) -> i128 {
+        0
+    }
+
+    fn sign(
+        &self,
+        _env: &Env,
+        _contract_id: &BytesN<32>,
+        _function: Symbol,
+        _args: impl IntoVal<Env, Vec<RawVal>>,
+    ) -> Signature {
+        Signature::Invoker
+    }
+
+    fn client(&self, env: &Env, contract_id: &BytesN<32>) -> CarRentalContractClient {
+        CarRentalContractClient::new(env, contract_id).with_source_account(self)
+    }
+}
+
+// Ed25519 keypair generated by CarRental::generate_signer.
+// The key also signs the client calls, e.g. open_request(signer.id.clone(), &signer.key)
+pub struct Ed25519Signer<K> {
+    pub id: Identifier,
+    pub key: K,
+}
+
+// The nonce is read from the contract, so it follows the calls signed outside of the testutils
+impl<K> Signer for Ed25519Signer<K>
+where
+    K: soroban_auth::testutils::ed25519::Identifier + Sign<SignaturePayload, Signature = [u8; 64]>,
+{
+    fn identifier(&self, _env: &Env) -> Identifier {
+        self.id.clone()
+    }
+
+    fn nonce(&self, env: &Env, contract_id: &BytesN<32>) -> i128 {
+        CarRentalContractClient::new(env, contract_id).nonce(&self.id)
+    }
+
+    fn sign(
+        &self,
+        env: &Env,
+        contract_id: &BytesN<32>,
+        function: Symbol,
+        args: impl IntoVal<Env, Vec<RawVal>>,
+    ) -> Signature {
+        soroban_auth::testutils::ed25519::sign(env, &self.key, contract_id, function, args)
+    }
+
+    fn client(&self, env: &Env, contract_id: &BytesN<32>) -> CarRentalContractClient {
+        CarRentalContractClient::new(env, contract_id)
+    }
+}
+
+// the data of the car informed to add_car and update_car
+fn car_spec(car_data: CarDataKey) -> CarSpec {
+    CarSpec {
+        model: car_data.model,
+        color: car_data.color,
+        horse: car_data.horse,
+        year: car_data.year,
+        mileage: car_data.mileage,
+        category: car_data.category,
+        home: car_data.home,
+        daily_rate: car_data.daily_rate,
+        deposit: car_data.deposit,
+    }
+}
 
 pub struct CarRental {
     env: Env,
@@ -18,82 +115,590 @@ impl CarRental {
         }
     }
 
-    pub fn init(&self, admin: &Identifier) {
-        CarRentalContractClient::new(&self.env, &self.contract_id).init(admin);
+    pub fn init(&self, admin: &Identifier, token_id: &BytesN<32>) {
+        CarRentalContractClient::new(&self.env, &self.contract_id).init(admin, token_id);
     }
 
-    // Admin methods (using Invoker Signature)
-    pub fn deny_request(&self, admin: &soroban_sdk::AccountId, client_id: Identifier) {
-        CarRentalContractClient::new(&self.env, &self.contract_id)
-            .with_source_account(&admin)
-            .deny_req(
-                &Signature::Invoker,
-                &client_id,
-                &BigInt::from_u32(&self.env, 0),
-            );
+    pub fn generate_signer(
+        &self,
+    ) -> Ed25519Signer<
+        impl soroban_auth::testutils::ed25519::Identifier
+            + Sign<SignaturePayload, Signature = [u8; 64]>
+            + Debug,
+    > {
+        let (id, key) = soroban_auth::testutils::ed25519::generate(&self.env);
+        Ed25519Signer { id, key }
     }
 
-    pub fn approve_request(&self, admin: &soroban_sdk::AccountId, client_id: Identifier) {
-        CarRentalContractClient::new(&self.env, &self.contract_id)
-            .with_source_account(&admin)
-            .appr_req(
-                &Signature::Invoker,
-                &client_id,
-                &BigInt::from_u32(&self.env, 0),
-            );
+    // Admin methods (signed by a Signer: an account or an Ed25519 keypair)
+    pub fn deny_request(&self, admin: &impl Signer, client_id: Identifier) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("deny_req"),
+            (&admin_id, &nonce, &client_id),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .deny_req(&sig, &client_id, &nonce);
     }
 
-    pub fn add_car(&self, admin: &soroban_sdk::AccountId, plate: &str, car_data: CarDataKey) {
-        CarRentalContractClient::new(&self.env, &self.contract_id)
-            .with_source_account(&admin)
-            .add_car(
-                &Signature::Invoker,
-                &BigInt::from_u32(&self.env, 0),
-                &plate.into_val(&self.env),
-                &car_data.model,
-                &car_data.color,
-                &car_data.horse,
-            );
+    pub fn batch_requests(
+        &self,
+        admin: &impl Signer,
+        approve: Vec<Identifier>,
+        deny: Vec<Identifier>,
+    ) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("batch_req"),
+            (&admin_id, &nonce, &approve, &deny),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .batch_req(&sig, &nonce, &approve, &deny);
     }
 
-    pub fn remove_car(&self, admin: &soroban_sdk::AccountId, plate: &str) {
-        CarRentalContractClient::new(&self.env, &self.contract_id)
-            .with_source_account(&admin)
-            .remove_car(
-                &Signature::Invoker,
-                &BigInt::from_u32(&self.env, 0),
-                &plate.into_val(&self.env),
-            );
+    pub fn set_validity(&self, admin: &impl Signer, ttl: u64) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_valid"),
+            (&admin_id, &nonce, &ttl),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_valid(&sig, &nonce, &ttl);
     }
 
-    pub fn deny_drop(&self, admin: &soroban_sdk::AccountId, plate: &str) {
-        CarRentalContractClient::new(&self.env, &self.contract_id)
-            .with_source_account(&admin)
-            .deny_drop(
-                &Signature::Invoker,
-                &BigInt::from_u32(&self.env, 0),
-                &plate.into_val(&self.env),
-            );
+    pub fn approve_request(&self, admin: &impl Signer, client_id: Identifier) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("appr_req"),
+            (&admin_id, &nonce, &client_id),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .appr_req(&sig, &client_id, &nonce);
     }
 
-    pub fn accept_drop(&self, admin: &soroban_sdk::AccountId, plate: &str) {
-        CarRentalContractClient::new(&self.env, &self.contract_id)
-            .with_source_account(&admin)
-            .accpt_drop(
-                &Signature::Invoker,
-                &BigInt::from_u32(&self.env, 0),
-                &plate.into_val(&self.env),
-            );
+    pub fn suspend_client(&self, admin: &impl Signer, client_id: Identifier) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("suspend"),
+            (&admin_id, &nonce, &client_id),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .suspend(&sig, &client_id, &nonce);
     }
 
-    pub fn set_admin(&self, admin: &soroban_sdk::AccountId, new_admin: Identifier) {
-        CarRentalContractClient::new(&self.env, &self.contract_id)
-            .with_source_account(&admin)
-            .set_admin(
-                &Signature::Invoker,
-                &BigInt::from_u32(&self.env, 0),
-                &new_admin,
-            );
+    pub fn reinstate_client(&self, admin: &impl Signer, client_id: Identifier) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("reinstate"),
+            (&admin_id, &nonce, &client_id),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .reinstate(&sig, &client_id, &nonce);
+    }
+
+    pub fn revoke_client(&self, admin: &impl Signer, client_id: Identifier) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("revoke"),
+            (&admin_id, &nonce, &client_id),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .revoke(&sig, &client_id, &nonce);
+    }
+
+    pub fn set_tier(&self, admin: &impl Signer, client_id: Identifier, tier: u32) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_tier"),
+            (&admin_id, &nonce, &client_id, &tier),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_tier(&sig, &nonce, &client_id, &tier);
+    }
+
+    pub fn set_limit(&self, admin: &impl Signer, tier: u32, limit: u32) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_limit"),
+            (&admin_id, &nonce, &tier, &limit),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_limit(&sig, &nonce, &tier, &limit);
+    }
+
+    pub fn set_auto_approval(&self, admin: &impl Signer, score: u32, count: u32) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_auto"),
+            (&admin_id, &nonce, &score, &count),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_auto(&sig, &nonce, &score, &count);
+    }
+
+    pub fn set_loyalty(&self, admin: &impl Signer, rentals: u32, bps: u32) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_loyal"),
+            (&admin_id, &nonce, &rentals, &bps),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_loyal(&sig, &nonce, &rentals, &bps);
+    }
+
+    pub fn score_return(&self, admin: &impl Signer, id: u32, score: u32) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("score_ret"),
+            (&admin_id, &nonce, &id, &score),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .score_ret(&sig, &nonce, &id, &score);
+    }
+
+    pub fn add_car(&self, admin: &impl Signer, plate: &str, car_data: CarDataKey) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let car = car_spec(car_data);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("add_car"),
+            (&admin_id, &nonce, &plate, &car),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .add_car(&sig, &nonce, &plate, &car);
+    }
+
+    pub fn update_car(&self, admin: &impl Signer, plate: &str, car_data: CarDataKey) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let car = car_spec(car_data);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("update_car"),
+            (&admin_id, &nonce, &plate, &car),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .update_car(&sig, &nonce, &plate, &car);
+    }
+
+    pub fn set_maint(&self, admin: &impl Signer, plate: &str, maint: bool) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_maint"),
+            (&admin_id, &nonce, &plate, &maint),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_maint(&sig, &nonce, &plate, &maint);
+    }
+
+    pub fn remove_car(&self, admin: &impl Signer, plate: &str) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("remove_car"),
+            (&admin_id, &nonce, &plate),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .remove_car(&sig, &nonce, &plate);
+    }
+
+    pub fn migrate_plate(&self, admin: &impl Signer, plate: &str) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("migr_plate"),
+            (&admin_id, &nonce, &plate),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .migr_plate(&sig, &nonce, &plate);
+    }
+
+    pub fn set_late(&self, admin: &impl Signer, plate: &str, grace: u64, late_rate: i128) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_late"),
+            (&admin_id, &nonce, &plate, &grace, &late_rate),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_late(&sig, &nonce, &plate, &grace, &late_rate);
+    }
+
+    pub fn set_usage(
+        &self,
+        admin: &impl Signer,
+        plate: &str,
+        km_rate: i128,
+        km_day: u64,
+        fuel_rate: i128,
+    ) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_usage"),
+            (&admin_id, &nonce, &plate, &km_rate, &km_day, &fuel_rate),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_usage(&sig, &nonce, &plate, &km_rate, &km_day, &fuel_rate);
+    }
+
+    pub fn set_branch(&self, admin: &impl Signer, branch: &str, reloc: i128) {
+        let branch: Bytes = branch.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_branch"),
+            (&admin_id, &nonce, &branch, &reloc),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_branch(&sig, &nonce, &branch, &reloc);
+    }
+
+    pub fn remove_branch(&self, admin: &impl Signer, branch: &str) {
+        let branch: Bytes = branch.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("rm_branch"),
+            (&admin_id, &nonce, &branch),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .rm_branch(&sig, &nonce, &branch);
+    }
+
+    pub fn relocate_car(&self, admin: &impl Signer, plate: &str, branch: &str) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let branch: Bytes = branch.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("reloc_car"),
+            (&admin_id, &nonce, &plate, &branch),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .reloc_car(&sig, &nonce, &plate, &branch);
+    }
+
+    pub fn add_maintenance(&self, admin: &impl Signer, plate: &str, start: u64, end: u64) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("add_maint"),
+            (&admin_id, &nonce, &plate, &start, &end),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .add_maint(&sig, &nonce, &plate, &start, &end);
+    }
+
+    pub fn remove_maintenance(&self, admin: &impl Signer, plate: &str, start: u64) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("rm_maint"),
+            (&admin_id, &nonce, &plate, &start),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .rm_maint(&sig, &nonce, &plate, &start);
+    }
+
+    pub fn service_done(&self, admin: &impl Signer, plate: &str, mileage: u64, cost: i128) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("svc_done"),
+            (&admin_id, &nonce, &plate, &mileage, &cost),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .svc_done(&sig, &nonce, &plate, &mileage, &cost);
+    }
+
+    pub fn set_service_rule(&self, admin: &impl Signer, rentals: u32, km: u64) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_svc"),
+            (&admin_id, &nonce, &rentals, &km),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_svc(&sig, &nonce, &rentals, &km);
+    }
+
+    pub fn add_insurance(&self, admin: &impl Signer, premium: i128, deduct: i128) -> u32 {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("add_insur"),
+            (&admin_id, &nonce, &premium, &deduct),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .add_insur(&sig, &nonce, &premium, &deduct)
+    }
+
+    pub fn set_weekend(&self, admin: &impl Signer, bps: u32) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_wkend"),
+            (&admin_id, &nonce, &bps),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_wkend(&sig, &nonce, &bps);
+    }
+
+    pub fn add_season(&self, admin: &impl Signer, start: u64, end: u64, bps: u32) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("add_season"),
+            (&admin_id, &nonce, &start, &end, &bps),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .add_season(&sig, &nonce, &start, &end, &bps);
+    }
+
+    pub fn remove_season(&self, admin: &impl Signer, start: u64) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("rm_season"),
+            (&admin_id, &nonce, &start),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .rm_season(&sig, &nonce, &start);
+    }
+
+    pub fn set_surge(&self, admin: &impl Signer, util: u32, bps: u32) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_surge"),
+            (&admin_id, &nonce, &util, &bps),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_surge(&sig, &nonce, &util, &bps);
+    }
+
+    pub fn set_policy(&self, admin: &impl Signer, ttl: u64, free: u64, fee: i128) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_policy"),
+            (&admin_id, &nonce, &ttl, &free, &fee),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_policy(&sig, &nonce, &ttl, &free, &fee);
+    }
+
+    pub fn deny_drop(
+        &self,
+        admin: &impl Signer,
+        plate: &str,
+        reason: u32,
+        cost: i128,
+        evidence: BytesN<32>,
+    ) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("deny_drop"),
+            (&admin_id, &nonce, &plate, &reason, &cost, &evidence),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .deny_drop(&sig, &nonce, &plate, &reason, &cost, &evidence);
+    }
+
+    pub fn settle(&self, admin: &impl Signer, plate: &str, amount: i128) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("settle"),
+            (&admin_id, &nonce, &plate, &amount),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .settle(&sig, &nonce, &plate, &amount);
+    }
+
+    pub fn accept_drop(&self, admin: &impl Signer, plate: &str, odometer: u64, fuel: u32) {
+        let plate: Bytes = plate.into_val(&self.env);
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("accpt_drop"),
+            (&admin_id, &nonce, &plate, &odometer, &fuel),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .accpt_drop(&sig, &nonce, &plate, &odometer, &fuel);
+    }
+
+    pub fn set_admin(&self, admin: &impl Signer, new_admin: Identifier) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("set_admin"),
+            (&admin_id, &nonce, &new_admin),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .set_admin(&sig, &nonce, &new_admin);
+    }
+
+    pub fn grant_role(&self, admin: &impl Signer, operator: Identifier, role: Role) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("grant_role"),
+            (&admin_id, &nonce, &operator, &role),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .grant_role(&sig, &nonce, &operator, &role);
+    }
+
+    pub fn revoke_role(&self, admin: &impl Signer, operator: Identifier, role: Role) {
+        let admin_id = admin.identifier(&self.env);
+        let nonce = admin.nonce(&self.env, &self.contract_id);
+        let sig = admin.sign(
+            &self.env,
+            &self.contract_id,
+            symbol!("revk_role"),
+            (&admin_id, &nonce, &operator, &role),
+        );
+        admin
+            .client(&self.env, &self.contract_id)
+            .revk_role(&sig, &nonce, &operator, &role);
     }
 
     // Client methods (using Ed25519 Signature)
@@ -101,10 +706,19 @@ impl CarRental {
         &self,
         user_id: Identifier,
         user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
-              + soroban_sdk::testutils::ed25519::Sign<
-            soroban_auth::SignaturePayload,
-            Signature = [u8; 64],
-        > + Debug),
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+    ) {
+        self.open_request_kyc(user_id, user_sign, BytesN::from_array(&self.env, &[0; 32]))
+    }
+
+    pub fn open_request_kyc(
+        &self,
+        user_id: Identifier,
+        user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+        kyc: BytesN<32>,
     ) {
         let nonce = self.nonce(&user_id);
         let sig = soroban_auth::testutils::ed25519::sign(
@@ -112,112 +726,481 @@ impl CarRental {
             user_sign,
             &self.contract_id,
             symbol!("open_req"),
-            (&user_id, &nonce),
+            (&user_id, &nonce, &kyc),
         );
-        CarRentalContractClient::new(&self.env, &self.contract_id).open_req(&sig, &nonce);
+        CarRentalContractClient::new(&self.env, &self.contract_id).open_req(&sig, &nonce, &kyc);
     }
 
     pub fn reserve_car(
         &self,
         user_id: Identifier,
         user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
-              + soroban_auth::testutils::ed25519::Sign<
-            soroban_auth::SignaturePayload,
-            Signature = [u8; 64],
-        > + Debug),
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+        plate: &str,
+        days: u32,
+    ) {
+        self.reserve_insured(user_id, user_sign, plate, days, 0)
+    }
+
+    pub fn reserve_insured(
+        &self,
+        user_id: Identifier,
+        user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
         plate: &str,
+        days: u32,
+        insurance: u32,
+    ) {
+        self.reserve_one_way(user_id, user_sign, plate, days, insurance, "")
+    }
+
+    pub fn reserve_one_way(
+        &self,
+        user_id: Identifier,
+        user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+        plate: &str,
+        days: u32,
+        insurance: u32,
+        dropoff: &str,
     ) {
         let nonce = self.nonce(&user_id);
+        let plate: Bytes = plate.into_val(&self.env);
+        let dropoff: Bytes = dropoff.into_val(&self.env);
         let sig = soroban_auth::testutils::ed25519::sign(
             &self.env,
             user_sign,
             &self.contract_id,
             symbol!("resrve_car"),
-            (&user_id, &nonce),
+            (&user_id, &nonce, &plate, &days, &insurance, &dropoff),
         );
-        CarRentalContractClient::new(&self.env, &self.contract_id).resrve_car(
-            &sig,
-            &nonce,
-            &plate.into_val(&self.env),
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .resrve_car(&sig, &nonce, &plate, &days, &insurance, &dropoff);
+    }
+
+    pub fn book_car(
+        &self,
+        user_id: Identifier,
+        user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+        plate: &str,
+        start: u64,
+        end: u64,
+    ) {
+        self.book_insured(user_id, user_sign, plate, start, end, 0)
+    }
+
+    pub fn book_insured(
+        &self,
+        user_id: Identifier,
+        user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+        plate: &str,
+        start: u64,
+        end: u64,
+        insurance: u32,
+    ) {
+        self.book_one_way(user_id, user_sign, plate, start, end, insurance, "", "")
+    }
+
+    pub fn book_one_way(
+        &self,
+        user_id: Identifier,
+        user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+        plate: &str,
+        start: u64,
+        end: u64,
+        insurance: u32,
+        pickup: &str,
+        dropoff: &str,
+    ) {
+        let nonce = self.nonce(&user_id);
+        let plate: Bytes = plate.into_val(&self.env);
+        let pickup: Bytes = pickup.into_val(&self.env);
+        let dropoff: Bytes = dropoff.into_val(&self.env);
+        let sig = soroban_auth::testutils::ed25519::sign(
+            &self.env,
+            user_sign,
+            &self.contract_id,
+            symbol!("book_car"),
+            (
+                &user_id, &nonce, &plate, &start, &end, &insurance, &pickup, &dropoff,
+            ),
+        );
+        CarRentalContractClient::new(&self.env, &self.contract_id).book_car(
+            &sig, &nonce, &plate, &start, &end, &insurance, &pickup, &dropoff,
         );
     }
 
+    pub fn cancel_booking(
+        &self,
+        user_id: Identifier,
+        user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+        plate: &str,
+        start: u64,
+    ) {
+        let nonce = self.nonce(&user_id);
+        let plate: Bytes = plate.into_val(&self.env);
+        let sig = soroban_auth::testutils::ed25519::sign(
+            &self.env,
+            user_sign,
+            &self.contract_id,
+            symbol!("cancl_book"),
+            (&user_id, &nonce, &plate, &start),
+        );
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .cancl_book(&sig, &nonce, &plate, &start);
+    }
+
+    pub fn cancel_reservation(
+        &self,
+        user_id: Identifier,
+        user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+        plate: &str,
+    ) {
+        let nonce = self.nonce(&user_id);
+        let plate: Bytes = plate.into_val(&self.env);
+        let sig = soroban_auth::testutils::ed25519::sign(
+            &self.env,
+            user_sign,
+            &self.contract_id,
+            symbol!("cancl_resv"),
+            (&user_id, &nonce, &plate),
+        );
+        CarRentalContractClient::new(&self.env, &self.contract_id).cancl_resv(&sig, &nonce, &plate);
+    }
+
+    pub fn release_expired(&self, plate: &str) {
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .rls_expird(&plate.into_val(&self.env));
+    }
+
     pub fn take_car(
         &self,
         user_id: Identifier,
         user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
-              + soroban_sdk::testutils::ed25519::Sign<
-            soroban_auth::SignaturePayload,
-            Signature = [u8; 64],
-        > + Debug),
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
         plate: &str,
+        odometer: u64,
+        fuel: u32,
     ) {
         let nonce = self.nonce(&user_id);
+        let plate: Bytes = plate.into_val(&self.env);
         let sig = soroban_auth::testutils::ed25519::sign(
             &self.env,
             user_sign,
             &self.contract_id,
             symbol!("take_car"),
-            (&user_id, &nonce),
-        );
-        CarRentalContractClient::new(&self.env, &self.contract_id).take_car(
-            &sig,
-            &nonce,
-            &plate.into_val(&self.env),
+            (&user_id, &nonce, &plate, &odometer, &fuel),
         );
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .take_car(&sig, &nonce, &plate, &odometer, &fuel);
     }
 
     pub fn drop_car(
         &self,
         user_id: Identifier,
         user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
-              + soroban_sdk::testutils::ed25519::Sign<
-            soroban_auth::SignaturePayload,
-            Signature = [u8; 64],
-        > + Debug),
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
         plate: &str,
+        odometer: u64,
+        fuel: u32,
     ) {
         let nonce = self.nonce(&user_id);
+        let plate: Bytes = plate.into_val(&self.env);
         let sig = soroban_auth::testutils::ed25519::sign(
             &self.env,
             user_sign,
             &self.contract_id,
             symbol!("drop_car"),
-            (&user_id, &nonce),
+            (&user_id, &nonce, &plate, &odometer, &fuel),
         );
-        CarRentalContractClient::new(&self.env, &self.contract_id).drop_car(
-            &sig,
-            &nonce,
-            &plate.into_val(&self.env),
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .drop_car(&sig, &nonce, &plate, &odometer, &fuel);
+    }
+
+    pub fn accept_damage(
+        &self,
+        user_id: Identifier,
+        user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+        plate: &str,
+    ) {
+        let nonce = self.nonce(&user_id);
+        let plate: Bytes = plate.into_val(&self.env);
+        let sig = soroban_auth::testutils::ed25519::sign(
+            &self.env,
+            user_sign,
+            &self.contract_id,
+            symbol!("accpt_dmg"),
+            (&user_id, &nonce, &plate),
         );
+        CarRentalContractClient::new(&self.env, &self.contract_id).accpt_dmg(&sig, &nonce, &plate);
+    }
+
+    pub fn rate_rental(
+        &self,
+        user_id: Identifier,
+        user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+        id: u32,
+        car: u32,
+        service: u32,
+    ) {
+        let nonce = self.nonce(&user_id);
+        let sig = soroban_auth::testutils::ed25519::sign(
+            &self.env,
+            user_sign,
+            &self.contract_id,
+            symbol!("rate_rent"),
+            (&user_id, &nonce, &id, &car, &service),
+        );
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .rate_rent(&sig, &nonce, &id, &car, &service);
+    }
+
+    pub fn dispute(
+        &self,
+        user_id: Identifier,
+        user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+        plate: &str,
+    ) {
+        let nonce = self.nonce(&user_id);
+        let plate: Bytes = plate.into_val(&self.env);
+        let sig = soroban_auth::testutils::ed25519::sign(
+            &self.env,
+            user_sign,
+            &self.contract_id,
+            symbol!("dispute"),
+            (&user_id, &nonce, &plate),
+        );
+        CarRentalContractClient::new(&self.env, &self.contract_id).dispute(&sig, &nonce, &plate);
+    }
+
+    pub fn pay_owed(
+        &self,
+        user_id: Identifier,
+        user_sign: &(impl soroban_auth::testutils::ed25519::Identifier
+              + Sign<SignaturePayload, Signature = [u8; 64]>
+              + Debug),
+    ) {
+        let nonce = self.nonce(&user_id);
+        let sig = soroban_auth::testutils::ed25519::sign(
+            &self.env,
+            user_sign,
+            &self.contract_id,
+            symbol!("pay_owed"),
+            (&user_id, &nonce),
+        );
+        CarRentalContractClient::new(&self.env, &self.contract_id).pay_owed(&sig, &nonce);
     }
 
     // Public methods
-    pub fn nonce(&self, id: &Identifier) -> BigInt {
+    pub fn nonce(&self, id: &Identifier) -> i128 {
         CarRentalContractClient::new(&self.env, &self.contract_id).nonce(id)
     }
 
     pub fn read_admin(&self) -> Identifier {
-        return CarRentalContractClient::new(&self.env, &self.contract_id).read_admin();
+        CarRentalContractClient::new(&self.env, &self.contract_id).read_admin()
     }
 
+    pub fn token_id(&self) -> BytesN<32> {
+        CarRentalContractClient::new(&self.env, &self.contract_id).token_id()
+    }
 
     pub fn read_client(&self, id: Identifier) -> ClientStatus {
-        return CarRentalContractClient::new(&self.env, &self.contract_id).read_clnt(&id);
+        CarRentalContractClient::new(&self.env, &self.contract_id).read_clnt(&id)
     }
 
     pub fn read_car(&self, plate: &str) -> CarDataKey {
-        return CarRentalContractClient::new(&self.env, &self.contract_id)
-            .read_car(&plate.into_val(&self.env));
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .read_car(&plate.into_val(&self.env))
     }
 
     pub fn read_rent(&self, plate: &str) -> RentedCarDataKey {
-        return CarRentalContractClient::new(&self.env, &self.contract_id)
-            .read_rent(&plate.into_val(&self.env));
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .read_rent(&plate.into_val(&self.env))
     }
 
     pub fn has_rent(&self, plate: &str) -> bool {
-        return CarRentalContractClient::new(&self.env, &self.contract_id)
-            .has_rent(&plate.into_val(&self.env));
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .has_rent(&plate.into_val(&self.env))
+    }
+
+    pub fn charges(&self, plate: &str) -> i128 {
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .charges(&plate.into_val(&self.env))
+    }
+
+    pub fn bookings(&self, plate: &str) -> Vec<Booking> {
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .bookings(&plate.into_val(&self.env))
+    }
+
+    pub fn available(&self, plate: &str, from: u64, to: u64) -> bool {
+        CarRentalContractClient::new(&self.env, &self.contract_id).available(
+            &plate.into_val(&self.env),
+            &from,
+            &to,
+        )
+    }
+
+    pub fn active(&self, client_id: Identifier) -> Vec<Bytes> {
+        CarRentalContractClient::new(&self.env, &self.contract_id).active(&client_id)
+    }
+
+    pub fn client_tier(&self, client_id: Identifier) -> u32 {
+        CarRentalContractClient::new(&self.env, &self.contract_id).clnt_tier(&client_id)
+    }
+
+    pub fn tier_limit(&self, tier: u32) -> u32 {
+        CarRentalContractClient::new(&self.env, &self.contract_id).tier_limit(&tier)
+    }
+
+    pub fn insurance(&self, id: u32) -> Insurance {
+        CarRentalContractClient::new(&self.env, &self.contract_id).insurance(&id)
+    }
+
+    pub fn pool(&self) -> i128 {
+        CarRentalContractClient::new(&self.env, &self.contract_id).pool()
+    }
+
+    pub fn maintenance_windows(&self, plate: &str) -> Vec<MaintWin> {
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .maint_wins(&plate.into_val(&self.env))
+    }
+
+    pub fn service_history(&self, plate: &str, offset: u32, limit: u32) -> Vec<ServiceRec> {
+        CarRentalContractClient::new(&self.env, &self.contract_id).svc_hist(
+            &plate.into_val(&self.env),
+            &offset,
+            &limit,
+        )
+    }
+
+    pub fn service_state(&self, plate: &str) -> SvcState {
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .svc_state(&plate.into_val(&self.env))
+    }
+
+    pub fn service_rule(&self) -> SvcRule {
+        CarRentalContractClient::new(&self.env, &self.contract_id).svc_rule()
+    }
+
+    pub fn branches(&self) -> Vec<Bytes> {
+        CarRentalContractClient::new(&self.env, &self.contract_id).branches()
+    }
+
+    pub fn relocation_fee(&self, branch: &str) -> i128 {
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .reloc_fee(&branch.into_val(&self.env))
+    }
+
+    pub fn pricing(&self) -> Pricing {
+        CarRentalContractClient::new(&self.env, &self.contract_id).pricing()
+    }
+
+    pub fn quote(&self, plate: &str, from: u64, to: u64) -> Quote {
+        CarRentalContractClient::new(&self.env, &self.contract_id).quote(
+            &plate.into_val(&self.env),
+            &from,
+            &to,
+        )
+    }
+
+    pub fn pending(&self, offset: u32, limit: u32) -> Vec<PendReq> {
+        CarRentalContractClient::new(&self.env, &self.contract_id).pending(&offset, &limit)
+    }
+
+    pub fn approval_expiry(&self, client_id: Identifier) -> u64 {
+        CarRentalContractClient::new(&self.env, &self.contract_id).appr_exp(&client_id)
+    }
+
+    pub fn owed(&self, client_id: &Identifier) -> i128 {
+        CarRentalContractClient::new(&self.env, &self.contract_id).owed(client_id)
+    }
+
+    pub fn reputation(&self, client_id: Identifier) -> Reputation {
+        CarRentalContractClient::new(&self.env, &self.contract_id).reputation(&client_id)
+    }
+
+    pub fn car_rating(&self, plate: &str) -> Rating {
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .car_rating(&plate.into_val(&self.env))
+    }
+
+    pub fn auto_approval(&self) -> AutoAppr {
+        CarRentalContractClient::new(&self.env, &self.contract_id).auto_appr()
+    }
+
+    pub fn loyalty(&self) -> Vec<Loyalty> {
+        CarRentalContractClient::new(&self.env, &self.contract_id).loyalty()
+    }
+
+    pub fn discount(&self, client_id: Identifier) -> u32 {
+        CarRentalContractClient::new(&self.env, &self.contract_id).discount(&client_id)
+    }
+
+    pub fn invoice(&self, id: u32) -> Invoice {
+        CarRentalContractClient::new(&self.env, &self.contract_id).invoice(&id)
+    }
+
+    pub fn list_cars(&self, offset: u32, limit: u32) -> Vec<CarInfo> {
+        CarRentalContractClient::new(&self.env, &self.contract_id).list_cars(&offset, &limit)
+    }
+
+    pub fn list_available(&self, offset: u32, limit: u32) -> Vec<CarInfo> {
+        self.list_available_at("", offset, limit)
+    }
+
+    pub fn list_available_at(&self, branch: &str, offset: u32, limit: u32) -> Vec<CarInfo> {
+        CarRentalContractClient::new(&self.env, &self.contract_id).list_avail(
+            &branch.into_val(&self.env),
+            &offset,
+            &limit,
+        )
+    }
+
+    pub fn rented_by(&self, client: &Identifier, offset: u32, limit: u32) -> Vec<CarInfo> {
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .rented_by(client, &offset, &limit)
+    }
+
+    pub fn car_history(&self, plate: &str, offset: u32, limit: u32) -> Vec<RentalRec> {
+        CarRentalContractClient::new(&self.env, &self.contract_id).car_hist(
+            &plate.into_val(&self.env),
+            &offset,
+            &limit,
+        )
+    }
+
+    pub fn client_history(&self, client: &Identifier, offset: u32, limit: u32) -> Vec<RentalRec> {
+        CarRentalContractClient::new(&self.env, &self.contract_id)
+            .clnt_hist(client, &offset, &limit)
+    }
+
+    pub fn has_role(&self, operator: &Identifier, role: Role) -> bool {
+        CarRentalContractClient::new(&self.env, &self.contract_id).has_role(operator, &role)
+    }
+
+    pub fn policy(&self) -> ResvPolicy {
+        CarRentalContractClient::new(&self.env, &self.contract_id).policy()
     }
 }